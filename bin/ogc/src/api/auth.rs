@@ -0,0 +1,166 @@
+use super::AccountId;
+use crate::mono::bot::User;
+
+use std::{fs, path::Path};
+
+use actix_web::{
+    dev::ServiceRequest,
+    error::{ErrorInternalServerError, ErrorNotFound, ErrorUnauthorized},
+    web::{self, Data, Json, ServiceConfig},
+    Error, HttpMessage, Result,
+};
+use actix_web_httpauth::extractors::bearer::BearerAuth;
+use anyhow::{anyhow, Context};
+use chrono::{Duration, Utc};
+use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+
+/// how long an issued token stays valid
+const TOKEN_TTL_HOURS: i64 = 12;
+
+/// RS256 key pair, tokens are signed with the private key and verified with the public key
+pub struct JwtKeys {
+    encoding: EncodingKey,
+    decoding: DecodingKey,
+}
+
+impl JwtKeys {
+    /// load PEM encoded RSA keys, e.g. generated by `openssl genrsa`
+    pub fn load(priv_file: &Path, pub_file: &Path) -> anyhow::Result<Self> {
+        let priv_pem = fs::read(priv_file)
+            .with_context(|| format!("read jwt private key {}", priv_file.display()))?;
+        let pub_pem = fs::read(pub_file)
+            .with_context(|| format!("read jwt public key {}", pub_file.display()))?;
+
+        Self::from_pem(&priv_pem, &pub_pem)
+    }
+
+    pub fn from_pem(priv_pem: &[u8], pub_pem: &[u8]) -> anyhow::Result<Self> {
+        Ok(JwtKeys {
+            encoding: EncodingKey::from_rsa_pem(priv_pem)?,
+            decoding: DecodingKey::from_rsa_pem(pub_pem)?,
+        })
+    }
+
+    pub fn issue(&self, claims: &Claims) -> anyhow::Result<String> {
+        Ok(encode(
+            &Header::new(Algorithm::RS256),
+            claims,
+            &self.encoding,
+        )?)
+    }
+
+    /// verify a token, rejecting one issued while the bot played another account
+    pub fn verify(&self, token: &str, account_id: AccountId) -> anyhow::Result<Claims> {
+        let claims =
+            decode::<Claims>(token, &self.decoding, &Validation::new(Algorithm::RS256))?.claims;
+        if claims.account_id != account_id.0 {
+            return Err(anyhow!("token is issued for another account"));
+        }
+
+        Ok(claims)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Claims {
+    /// the game account the token was issued for
+    pub sub: String,
+    pub account_id: i32,
+    pub iat: i64,
+    pub exp: i64,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Login {
+    account: String,
+    password: String,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Token {
+    access_token: String,
+    token_type: &'static str,
+    expires_in: i64,
+}
+
+pub fn routes(prefix: &'static str) -> impl FnOnce(&mut ServiceConfig) {
+    move |cfg| {
+        cfg.service(web::scope(prefix).route("/login", web::post().to(login)));
+    }
+}
+
+/// issue a token for the account the bot plays
+async fn login(
+    keys: Data<JwtKeys>,
+    user: Data<User>,
+    account_id: Data<AccountId>,
+    login: Json<Login>,
+) -> Result<Json<Token>> {
+    if login.account != user.account || login.password != user.password {
+        return Err(ErrorUnauthorized("invalid account or password"));
+    }
+
+    let now = Utc::now();
+    let claims = Claims {
+        sub: user.account.clone(),
+        account_id: account_id.0,
+        iat: now.timestamp(),
+        exp: (now + Duration::hours(TOKEN_TTL_HOURS)).timestamp(),
+    };
+    let access_token = keys.issue(&claims).map_err(ErrorInternalServerError)?;
+
+    Ok(Json(Token {
+        access_token,
+        token_type: "Bearer",
+        expires_in: TOKEN_TTL_HOURS * 3600,
+    }))
+}
+
+/// bearer token of the prometheus scraper, `/metrics` is not served without one
+#[derive(Clone, Debug, Default)]
+pub struct MetricsToken(pub Option<String>);
+
+/// compare the bearer token with the scrape token
+pub async fn metrics_validator(
+    req: ServiceRequest,
+    credentials: BearerAuth,
+) -> std::result::Result<ServiceRequest, (Error, ServiceRequest)> {
+    let expected = req
+        .app_data::<Data<MetricsToken>>()
+        .and_then(|token| token.0.clone());
+
+    match expected {
+        Some(expected) if credentials.token() == expected => Ok(req),
+        Some(_) => Err((ErrorUnauthorized("invalid metrics token"), req)),
+        None => Err((
+            ErrorNotFound("metrics are only served with a --metrics-token"),
+            req,
+        )),
+    }
+}
+
+/// verify the bearer token, the claims are stored in the request extensions
+pub async fn validator(
+    req: ServiceRequest,
+    credentials: BearerAuth,
+) -> std::result::Result<ServiceRequest, (Error, ServiceRequest)> {
+    let (keys, account_id) = match (
+        req.app_data::<Data<JwtKeys>>(),
+        req.app_data::<Data<AccountId>>(),
+    ) {
+        (Some(keys), Some(account_id)) => (keys, *account_id.get_ref()),
+        _ => return Err((ErrorInternalServerError("jwt keys are not loaded"), req)),
+    };
+
+    match keys.verify(credentials.token(), account_id) {
+        Ok(claims) => {
+            req.extensions_mut().insert(claims);
+            Ok(req)
+        }
+        Err(e) => Err((ErrorUnauthorized(e), req)),
+    }
+}
@@ -0,0 +1,80 @@
+use crate::mono::bot::{BotCommand, BotStatus, SharedOverview};
+
+use actix_web::{
+    error::{ErrorInternalServerError, ErrorNotFound, ErrorServiceUnavailable},
+    web::{self, Data, Json, Path, ServiceConfig},
+    HttpResponse, Result,
+};
+use tokio::sync::{mpsc, oneshot, watch};
+
+/// sender of the commands served by the bot loop
+pub type BotCommands = mpsc::Sender<BotCommand>;
+
+pub fn routes(cfg: &mut ServiceConfig) {
+    cfg.route("/bot/status", web::get().to(status))
+        .route("/bot/pause", web::post().to(pause))
+        .route("/bot/resume", web::post().to(resume))
+        .route("/bot/refresh", web::post().to(refresh))
+        .route("/planets/{id}/fleet-save", web::post().to(fleet_save));
+}
+
+/// current loop state and next scheduled refresh
+async fn status(status: Data<watch::Receiver<BotStatus>>) -> Json<BotStatus> {
+    Json(status.borrow().clone())
+}
+
+async fn send(commands: &BotCommands, command: BotCommand) -> Result<()> {
+    commands
+        .send(command)
+        .await
+        .map_err(|_| ErrorServiceUnavailable("bot is not running"))
+}
+
+/// commands are served between refreshes, a running refresh finishes first
+async fn pause(commands: Data<BotCommands>) -> Result<HttpResponse> {
+    send(&commands, BotCommand::Pause).await?;
+    Ok(HttpResponse::Accepted().finish())
+}
+
+async fn resume(commands: Data<BotCommands>) -> Result<HttpResponse> {
+    send(&commands, BotCommand::Resume).await?;
+    Ok(HttpResponse::Accepted().finish())
+}
+
+async fn refresh(commands: Data<BotCommands>) -> Result<HttpResponse> {
+    send(&commands, BotCommand::RefreshNow).await?;
+    Ok(HttpResponse::Accepted().finish())
+}
+
+/// send the fleet of a planet or moon away, responds once the fleet is dispatched
+async fn fleet_save(
+    commands: Data<BotCommands>,
+    overview: Data<SharedOverview>,
+    id: Path<String>,
+) -> Result<HttpResponse> {
+    let known = overview
+        .read()
+        .await
+        .as_ref()
+        .is_some_and(|latest| latest.empire_overview.bodies().any(|body| body.id == *id));
+    if !known {
+        return Err(ErrorNotFound("planet or moon not found"));
+    }
+
+    let (reply, result) = oneshot::channel();
+    send(
+        &commands,
+        BotCommand::FleetSave {
+            planet_id: id.into_inner(),
+            reply,
+        },
+    )
+    .await?;
+
+    result
+        .await
+        .map_err(|_| ErrorServiceUnavailable("bot stopped before saving the fleet"))?
+        .map_err(ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().finish())
+}
@@ -0,0 +1,615 @@
+pub mod auth;
+pub mod control;
+
+use self::{
+    auth::{JwtKeys, MetricsToken},
+    control::BotCommands,
+};
+use crate::mono::bot::{BotEvent, BotEvents, BotStatus, LatestOverview, SharedOverview, User};
+
+use ogc_core::{
+    model::{PlanetOverview, Technology},
+    sqlx_postgres::{
+        fleet_event::{open_fleet_events, FleetEventRecord},
+        history::{resource_between, ResourceSnapshot},
+        sqlx::PgPool,
+    },
+};
+
+use actix_cors::Cors;
+use actix_web::{
+    error::{ErrorInternalServerError, ErrorNotFound},
+    http::header,
+    web::{self, Bytes, Data, Json, Path, Query, ServiceConfig},
+    HttpResponse, Result,
+};
+use actix_web_httpauth::middleware::HttpAuthentication;
+use chrono::{DateTime, Duration, Utc};
+use futures::stream;
+use serde::Deserialize;
+use tokio::{
+    sync::{broadcast::error::RecvError, watch},
+    time::{interval, Duration as StdDuration, MissedTickBehavior},
+};
+
+/// comment line sent on an idle event stream so that proxies keep the connection open
+const KEEP_ALIVE_SECS: u64 = 15;
+
+/// account the bot plays, every database query of the api is scoped by it
+#[derive(Clone, Copy, Debug)]
+pub struct AccountId(pub i32);
+
+/// state the api shares with the bot, every field is registered as app data
+#[derive(Clone)]
+pub struct ApiState {
+    pub pg_pool: PgPool,
+    pub account_id: AccountId,
+    pub user: User,
+    pub jwt_keys: Data<JwtKeys>,
+    pub metrics_token: MetricsToken,
+    pub overview: SharedOverview,
+    pub events: BotEvents,
+    pub status: watch::Receiver<BotStatus>,
+    pub commands: BotCommands,
+}
+
+/// the whole http service, `state` is registered as app data
+pub fn configure(state: ApiState) -> impl FnOnce(&mut ServiceConfig) {
+    move |cfg| {
+        cfg.app_data(Data::new(state.pg_pool))
+            .app_data(Data::new(state.account_id))
+            .app_data(Data::new(state.user))
+            .app_data(state.jwt_keys)
+            .app_data(Data::new(state.metrics_token))
+            .app_data(Data::new(state.overview))
+            .app_data(Data::new(state.events))
+            .app_data(Data::new(state.status))
+            .app_data(Data::new(state.commands))
+            .route("/healthz", web::get().to(HttpResponse::Ok))
+            .service(
+                web::resource("/metrics")
+                    .wrap(HttpAuthentication::bearer(auth::metrics_validator))
+                    .route(web::get().to(crate::metrics::serve)),
+            )
+            .service(
+                web::scope("secure")
+                    .wrap(
+                        Cors::default()
+                            .allowed_origin("http://localhost:1234")
+                            .allowed_origin("http://127.0.0.1:1234")
+                            .allowed_methods(vec!["POST", "GET", "PUT", "DELETE"])
+                            .allowed_headers(vec![
+                                header::CONTENT_TYPE,
+                                header::AUTHORIZATION,
+                                header::ACCEPT,
+                            ])
+                            .max_age(3600)
+                            .supports_credentials(),
+                    )
+                    .configure(auth::routes("auth"))
+                    .configure(routes("api")),
+            );
+    }
+}
+
+pub fn routes(prefix: &'static str) -> impl FnOnce(&mut ServiceConfig) {
+    move |cfg| {
+        cfg.service(
+            web::scope(prefix)
+                .wrap(HttpAuthentication::bearer(auth::validator))
+                .route("/overview", web::get().to(overview))
+                .route("/planets/{id}", web::get().to(planet))
+                .route("/planets/{id}/resources", web::get().to(planet_resources))
+                .route("/technology", web::get().to(technology))
+                .route("/fleet-events", web::get().to(fleet_events))
+                .route("/events", web::get().to(events))
+                .configure(control::routes),
+        );
+    }
+}
+
+/// latest empire overview, refreshed by the bot
+async fn overview(overview: Data<SharedOverview>) -> Result<Json<LatestOverview>> {
+    let latest = overview.read().await;
+    let latest = latest
+        .as_ref()
+        .ok_or_else(|| ErrorNotFound("empire overview is not refreshed yet"))?;
+
+    Ok(Json(latest.clone()))
+}
+
+/// latest overview of a single planet, `id` is the in-game planet id
+async fn planet(overview: Data<SharedOverview>, id: Path<String>) -> Result<Json<PlanetOverview>> {
+    let latest = overview.read().await;
+    let latest = latest
+        .as_ref()
+        .ok_or_else(|| ErrorNotFound("empire overview is not refreshed yet"))?;
+
+    let planet = latest
+        .empire_overview
+        .overview
+        .iter()
+        .find(|planet| planet.id == *id)
+        .ok_or_else(|| ErrorNotFound("planet not found"))?;
+
+    Ok(Json(planet.clone()))
+}
+
+#[derive(Deserialize, Debug)]
+struct TimeRange {
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+}
+
+/// resource snapshots of a planet, the last 24 hours by default
+async fn planet_resources(
+    pg_pool: Data<PgPool>,
+    account_id: Data<AccountId>,
+    overview: Data<SharedOverview>,
+    id: Path<String>,
+    range: Query<TimeRange>,
+) -> Result<Json<Vec<ResourceSnapshot>>> {
+    let location = {
+        let latest = overview.read().await;
+        latest
+            .as_ref()
+            .and_then(|latest| {
+                latest
+                    .empire_overview
+                    .overview
+                    .iter()
+                    .find(|planet| planet.id == *id)
+            })
+            .map(|planet| planet.location)
+            .ok_or_else(|| ErrorNotFound("planet not found"))?
+    };
+
+    let to = range.to.unwrap_or_else(Utc::now);
+    let from = range.from.unwrap_or(to - Duration::hours(24));
+
+    let snapshots = resource_between(&pg_pool, account_id.0, &location, from, to)
+        .await
+        .map_err(ErrorInternalServerError)?;
+
+    Ok(Json(snapshots))
+}
+
+/// account-wide technology levels
+async fn technology(overview: Data<SharedOverview>) -> Result<Json<Technology>> {
+    let latest = overview.read().await;
+    let latest = latest
+        .as_ref()
+        .ok_or_else(|| ErrorNotFound("empire overview is not refreshed yet"))?;
+
+    Ok(Json(latest.empire_overview.technology.clone()))
+}
+
+/// fleet events still listed in the latest refresh
+async fn fleet_events(
+    pg_pool: Data<PgPool>,
+    account_id: Data<AccountId>,
+) -> Result<Json<Vec<FleetEventRecord>>> {
+    let events = open_fleet_events(&pg_pool, account_id.0)
+        .await
+        .map_err(ErrorInternalServerError)?;
+
+    Ok(Json(events))
+}
+
+/// server-sent events of the bot loop, each message is named after the event type
+async fn events(events: Data<BotEvents>) -> HttpResponse {
+    let mut keep_alive = interval(StdDuration::from_secs(KEEP_ALIVE_SECS));
+    keep_alive.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    let stream = stream::unfold(
+        (events.subscribe(), keep_alive),
+        |(mut rx, mut keep_alive)| async move {
+            let message = loop {
+                tokio::select! {
+                    event = rx.recv() => match event {
+                        Ok(event) => break sse_message(&event),
+                        Err(RecvError::Lagged(skipped)) => {
+                            log::warn!("event stream lagged, {} events skipped", skipped);
+                        }
+                        Err(RecvError::Closed) => return None,
+                    },
+                    _ = keep_alive.tick() => break Bytes::from_static(b": keep-alive\n\n"),
+                }
+            };
+
+            Some((Ok::<_, actix_web::Error>(message), (rx, keep_alive)))
+        },
+    );
+
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header(("Cache-Control", "no-cache"))
+        .streaming(stream)
+}
+
+fn sse_message(event: &BotEvent) -> Bytes {
+    let name: &'static str = event.into();
+    // serializing the event is infallible, it only holds strings, numbers and timestamps
+    let data = serde_json::to_string(event).unwrap_or_default();
+
+    Bytes::from(format!("event: {}\ndata: {}\n\n", name, data))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::mono::{
+        bot::BotState,
+        fleet_save::FleetSaveConfig,
+        mock::{home_game, spawn_bot, MockBot, LOCATION},
+    };
+
+    use std::{
+        collections::BTreeMap,
+        future::poll_fn,
+        pin::Pin,
+        sync::{Arc, Mutex},
+    };
+
+    use actix_web::{
+        body::MessageBody,
+        http::StatusCode,
+        test::{self, TestRequest},
+        App,
+    };
+    use serde_json::{json, Value};
+    use tokio::time::timeout;
+
+    // a throwaway pair, only ever used by the tests
+    const JWT_PRIV_PEM: &[u8] = include_bytes!("testdata/jwt.pem");
+    const JWT_PUB_PEM: &[u8] = include_bytes!("testdata/jwt.pub.pem");
+
+    fn jwt_keys() -> JwtKeys {
+        JwtKeys::from_pem(JWT_PRIV_PEM, JWT_PUB_PEM).expect("test jwt keys")
+    }
+
+    /// what `mono` serves for `bot`
+    pub(crate) fn api_state(bot: &MockBot) -> ApiState {
+        ApiState {
+            pg_pool: bot.pg_pool.clone(),
+            account_id: AccountId(1),
+            user: User {
+                account: "player@example.com".to_owned(),
+                password: "secret".to_owned(),
+                universe: "s144-tw".to_owned(),
+            },
+            jwt_keys: Data::new(jwt_keys()),
+            metrics_token: MetricsToken(Some("scrape-token".to_owned())),
+            overview: bot.handles.overview.clone(),
+            events: bot.handles.events.clone(),
+            status: bot.status.clone(),
+            commands: bot.commands.clone(),
+        }
+    }
+
+    /// wait until the bot is `state`
+    pub(crate) async fn until_state(bot: &mut MockBot, state: BotState) -> anyhow::Result<()> {
+        timeout(
+            StdDuration::from_secs(30),
+            bot.status.wait_for(|status| status.state == state),
+        )
+        .await??;
+
+        Ok(())
+    }
+
+    /// a token of the account the bot plays, as the login issues it
+    pub(crate) fn token(account_id: i32, issued_at: DateTime<Utc>) -> String {
+        jwt_keys()
+            .issue(&auth::Claims {
+                sub: "player@example.com".to_owned(),
+                account_id,
+                iat: issued_at.timestamp(),
+                exp: (issued_at + Duration::hours(12)).timestamp(),
+            })
+            .expect("issue token")
+    }
+
+    fn get(uri: &str, token: &str) -> TestRequest {
+        TestRequest::get()
+            .uri(uri)
+            .insert_header(("authorization", format!("Bearer {}", token)))
+    }
+
+    #[actix_web::test]
+    async fn rejects_missing_or_foreign_token() -> anyhow::Result<()> {
+        let game = Arc::new(Mutex::new(home_game()));
+        let bot = spawn_bot(game, FleetSaveConfig::default()).await?;
+        let app = test::init_service(App::new().configure(configure(api_state(&bot)))).await;
+
+        let req = TestRequest::post()
+            .uri("/secure/auth/login")
+            .set_json(json!({ "account": "player@example.com", "password": "wrong" }))
+            .to_request();
+        assert_eq!(
+            test::call_service(&app, req).await.status(),
+            StatusCode::UNAUTHORIZED
+        );
+
+        let req = TestRequest::get().uri("/secure/api/overview").to_request();
+        assert_eq!(
+            test::call_service(&app, req).await.status(),
+            StatusCode::UNAUTHORIZED
+        );
+        let garbage = get("/secure/api/overview", "garbage").to_request();
+        assert_eq!(
+            test::call_service(&app, garbage).await.status(),
+            StatusCode::UNAUTHORIZED
+        );
+
+        // signed by the same key, but for another account the bot does not play
+        let foreign = token(2, Utc::now());
+        let foreign = get("/secure/api/overview", &foreign).to_request();
+        assert_eq!(
+            test::call_service(&app, foreign).await.status(),
+            StatusCode::UNAUTHORIZED
+        );
+        let expired = token(1, Utc::now() - Duration::hours(13));
+        let expired = get("/secure/api/overview", &expired).to_request();
+        assert_eq!(
+            test::call_service(&app, expired).await.status(),
+            StatusCode::UNAUTHORIZED
+        );
+
+        let req = TestRequest::post()
+            .uri("/secure/auth/login")
+            .set_json(json!({ "account": "player@example.com", "password": "secret" }))
+            .to_request();
+        let login: Value = test::call_and_read_body_json(&app, req).await;
+        let token = login["accessToken"].as_str().expect("access token");
+        let status = get("/secure/api/bot/status", token).to_request();
+        assert_eq!(
+            test::call_service(&app, status).await.status(),
+            StatusCode::OK
+        );
+        bot.stop().await;
+
+        Ok(())
+    }
+
+    #[actix_web::test]
+    async fn metrics_require_scrape_token() -> anyhow::Result<()> {
+        let game = Arc::new(Mutex::new(home_game()));
+        let bot = spawn_bot(game, FleetSaveConfig::default()).await?;
+        let state = api_state(&bot);
+        let app = test::init_service(App::new().configure(configure(state.clone()))).await;
+
+        let unauthorized = TestRequest::get().uri("/metrics").to_request();
+        assert_eq!(
+            test::call_service(&app, unauthorized).await.status(),
+            StatusCode::UNAUTHORIZED
+        );
+        // a token of the api is no scrape token
+        let jwt = get("/metrics", &token(1, Utc::now())).to_request();
+        assert_eq!(
+            test::call_service(&app, jwt).await.status(),
+            StatusCode::UNAUTHORIZED
+        );
+        let scrape = get("/metrics", "scrape-token").to_request();
+        assert_eq!(
+            test::call_service(&app, scrape).await.status(),
+            StatusCode::OK
+        );
+
+        let disabled = test::init_service(App::new().configure(configure(ApiState {
+            metrics_token: MetricsToken(None),
+            ..state
+        })))
+        .await;
+        let scrape = get("/metrics", "scrape-token").to_request();
+        assert_eq!(
+            test::call_service(&disabled, scrape).await.status(),
+            StatusCode::NOT_FOUND
+        );
+        bot.stop().await;
+
+        Ok(())
+    }
+
+    #[actix_web::test]
+    async fn serves_latest_overview() -> anyhow::Result<()> {
+        let game = Arc::new(Mutex::new(home_game()));
+        let mut bot = spawn_bot(game, FleetSaveConfig::default()).await?;
+        let app = test::init_service(App::new().configure(configure(api_state(&bot)))).await;
+        let token = token(1, Utc::now());
+
+        until_state(&mut bot, BotState::Sleeping).await?;
+        let overview: Value =
+            test::call_and_read_body_json(&app, get("/secure/api/overview", &token).to_request())
+                .await;
+        let planets = overview["empireOverview"]["overview"]
+            .as_array()
+            .expect("planets");
+        assert_eq!(planets.len(), 1);
+        assert_eq!(planets[0]["id"], "1001");
+        assert_eq!(planets[0]["lunar"]["id"], "1002");
+
+        let planet = get("/secure/api/planets/1001", &token).to_request();
+        let planet: Value = test::call_and_read_body_json(&app, planet).await;
+        assert_eq!(planet["resource"]["metal"], 300_000);
+        assert_eq!(planet["fleet"]["largeCargoShip"], 10);
+        let missing = get("/secure/api/planets/9999", &token).to_request();
+        assert_eq!(
+            test::call_service(&app, missing).await.status(),
+            StatusCode::NOT_FOUND
+        );
+
+        let technology = get("/secure/api/technology", &token).to_request();
+        assert_eq!(
+            test::call_service(&app, technology).await.status(),
+            StatusCode::OK
+        );
+        bot.stop().await;
+
+        Ok(())
+    }
+
+    #[actix_web::test]
+    async fn streams_bot_events() -> anyhow::Result<()> {
+        let game = Arc::new(Mutex::new(home_game()));
+        let bot = spawn_bot(game, FleetSaveConfig::default()).await?;
+        let app = test::init_service(App::new().configure(configure(api_state(&bot)))).await;
+
+        let unauthorized = TestRequest::get().uri("/secure/api/events").to_request();
+        assert_eq!(
+            test::call_service(&app, unauthorized).await.status(),
+            StatusCode::UNAUTHORIZED
+        );
+
+        let events = get("/secure/api/events", &token(1, Utc::now())).to_request();
+        let response = test::call_service(&app, events).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers().get("content-type").unwrap(),
+            "text/event-stream"
+        );
+        let saved_at = Utc::now();
+        bot.handles.events.send(BotEvent::FleetSaved {
+            planet_id: "1002".to_owned(),
+            location: LOCATION.moon(),
+            saved_at,
+        })?;
+
+        // the state changes of the bot come in between
+        let mut body = response.into_body();
+        let message = timeout(StdDuration::from_secs(10), async {
+            loop {
+                let chunk = poll_fn(|cx| Pin::new(&mut body).poll_next(cx))
+                    .await
+                    .expect("endless stream")
+                    .expect("sse chunk");
+                let message = String::from_utf8(chunk.to_vec()).expect("utf-8");
+                if message.starts_with("event: fleetSaved\n") {
+                    break message;
+                }
+            }
+        })
+        .await?;
+        let data: Value = serde_json::from_str(
+            message
+                .trim_end()
+                .strip_prefix("event: fleetSaved\ndata: ")
+                .expect("data line"),
+        )?;
+        assert_eq!(data["type"], "fleetSaved");
+        assert_eq!(data["data"]["planetId"], "1002");
+        assert_eq!(data["data"]["savedAt"], serde_json::to_value(saved_at)?);
+        bot.stop().await;
+
+        Ok(())
+    }
+
+    /// wait for the next time the bot turns `state`, in the order of its events
+    async fn next_state(bot: &mut MockBot, state: BotState) -> anyhow::Result<()> {
+        timeout(StdDuration::from_secs(30), async {
+            loop {
+                if let BotEvent::StateChanged(status) = bot.events.recv().await? {
+                    if status.state == state {
+                        return anyhow::Ok(());
+                    }
+                }
+            }
+        })
+        .await?
+    }
+
+    fn post(uri: &str, token: &str) -> TestRequest {
+        TestRequest::post()
+            .uri(uri)
+            .insert_header(("authorization", format!("Bearer {}", token)))
+    }
+
+    #[actix_web::test]
+    async fn controls_bot_loop() -> anyhow::Result<()> {
+        let game = Arc::new(Mutex::new(home_game()));
+        let mut bot = spawn_bot(game.clone(), FleetSaveConfig::default()).await?;
+        let app = test::init_service(App::new().configure(configure(api_state(&bot)))).await;
+        let token = token(1, Utc::now());
+        next_state(&mut bot, BotState::Sleeping).await?;
+
+        let unauthorized = TestRequest::post()
+            .uri("/secure/api/bot/pause")
+            .to_request();
+        assert_eq!(
+            test::call_service(&app, unauthorized).await.status(),
+            StatusCode::UNAUTHORIZED
+        );
+
+        let pause = post("/secure/api/bot/pause", &token).to_request();
+        assert_eq!(
+            test::call_service(&app, pause).await.status(),
+            StatusCode::ACCEPTED
+        );
+        next_state(&mut bot, BotState::Paused).await?;
+        let status = get("/secure/api/bot/status", &token).to_request();
+        let status: Value = test::call_and_read_body_json(&app, status).await;
+        assert_eq!(status["state"], "paused");
+        assert_eq!(status["nextRefreshAt"], Value::Null);
+
+        let resume = post("/secure/api/bot/resume", &token).to_request();
+        assert_eq!(
+            test::call_service(&app, resume).await.status(),
+            StatusCode::ACCEPTED
+        );
+        next_state(&mut bot, BotState::Sleeping).await?;
+        let status = get("/secure/api/bot/status", &token).to_request();
+        let status: Value = test::call_and_read_body_json(&app, status).await;
+        assert_eq!(status["state"], "sleeping");
+        assert!(status["nextRefreshAt"].is_string());
+
+        let refresh = post("/secure/api/bot/refresh", &token).to_request();
+        assert_eq!(
+            test::call_service(&app, refresh).await.status(),
+            StatusCode::ACCEPTED
+        );
+        next_state(&mut bot, BotState::Refreshing).await?;
+        next_state(&mut bot, BotState::Sleeping).await?;
+
+        let missing = post("/secure/api/planets/9999/fleet-save", &token).to_request();
+        assert_eq!(
+            test::call_service(&app, missing).await.status(),
+            StatusCode::NOT_FOUND
+        );
+        let fleet_save = post("/secure/api/planets/1001/fleet-save", &token).to_request();
+        assert_eq!(
+            test::call_service(&app, fleet_save).await.status(),
+            StatusCode::OK
+        );
+        next_state(&mut bot, BotState::FleetSaving).await?;
+        bot.stop().await;
+
+        let game = game.lock().expect("mock game lock");
+        assert_eq!(game.dispatches.len(), 1);
+        assert_eq!(game.dispatches[0].from, "1001");
+
+        Ok(())
+    }
+
+    #[actix_web::test]
+    async fn saves_fleet_of_moon() -> anyhow::Result<()> {
+        let game = Arc::new(Mutex::new(home_game()));
+        let mut bot = spawn_bot(game.clone(), FleetSaveConfig::default()).await?;
+        let app = test::init_service(App::new().configure(configure(api_state(&bot)))).await;
+        let token = token(1, Utc::now());
+        next_state(&mut bot, BotState::Sleeping).await?;
+
+        let fleet_save = post("/secure/api/planets/1002/fleet-save", &token).to_request();
+        assert_eq!(
+            test::call_service(&app, fleet_save).await.status(),
+            StatusCode::OK
+        );
+        bot.stop().await;
+
+        let game = game.lock().expect("mock game lock");
+        assert_eq!(game.dispatches.len(), 1);
+        assert_eq!(game.dispatches[0].from, "1002");
+        assert_eq!(game.dispatches[0].ships, BTreeMap::from([(202, 4)]));
+
+        Ok(())
+    }
+}
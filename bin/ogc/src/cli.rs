@@ -1,6 +1,6 @@
 use crate::mono::Opts;
 
-use std::{fs, path::PathBuf};
+use std::path::PathBuf;
 
 use clap::{Parser, ValueHint};
 use serde::Serialize;
@@ -37,6 +37,7 @@ pub enum Environment {
     Production,
 }
 
+#[allow(dead_code)]
 impl Environment {
     pub fn prod(&self) -> bool {
         matches!(self, Environment::Production)
@@ -0,0 +1,493 @@
+use crate::{
+    api::{auth::JwtKeys, AccountId, ApiState},
+    mono::{
+        bot::{self, BotCommand},
+        dispatch::Dispatch,
+        fleet_save::Load,
+    },
+};
+
+use std::{collections::HashMap, net::SocketAddr, pin::Pin, sync::Arc};
+
+use ogc_core::{
+    model::{Lunar, PlanetOverview, Resource},
+    sqlx_postgres::fleet_event::{open_fleet_events, FleetEventRecord},
+};
+use ogc_proto::{
+    bot_event::Event,
+    bot_server::{Bot, BotServer},
+    prost_types::Timestamp,
+    BotEvent, BotState, BotStatus, DispatchRequest, DispatchResponse, Empire, FleetEvent,
+    FleetRecalled, FleetSaveRequest, FleetSaveResponse, FleetSaved, GetEmpireRequest,
+    GetStatusRequest, ListFleetEventsRequest, ListFleetEventsResponse, Moon, PauseRequest,
+    PauseResponse, Planet, RefreshRequest, RefreshResponse, ResumeRequest, ResumeResponse,
+    SubscribeEventsRequest,
+};
+
+use chrono::{DateTime, Utc};
+use futures::{stream, Stream};
+use serde::Serialize;
+use tokio::{
+    net::TcpListener,
+    sync::{broadcast::error::RecvError, oneshot},
+};
+use tonic::{transport::Server, Request, Response, Status};
+
+/// serve the bot service, every call must carry a token issued by the http api
+pub async fn serve(addr: SocketAddr, state: ApiState) -> anyhow::Result<()> {
+    serve_on(TcpListener::bind(addr).await?, state).await
+}
+
+/// serve the bot service on a bound listener, e.g. on a free port
+// the interceptor must return a `tonic::Status`, however large it is
+#[allow(clippy::result_large_err)]
+async fn serve_on(listener: TcpListener, state: ApiState) -> anyhow::Result<()> {
+    let keys = state.jwt_keys.clone().into_inner();
+    let account_id = state.account_id;
+    let incoming = stream::unfold(listener, |listener| async move {
+        let stream = listener.accept().await.map(|(stream, _)| stream);
+        Some((stream, listener))
+    });
+
+    Server::builder()
+        .add_service(BotServer::with_interceptor(
+            BotService { state },
+            move |req: Request<()>| authenticate(req, &keys, account_id),
+        ))
+        .serve_with_incoming(incoming)
+        .await?;
+
+    Ok(())
+}
+
+#[allow(clippy::result_large_err)]
+fn authenticate(
+    req: Request<()>,
+    keys: &Arc<JwtKeys>,
+    account_id: AccountId,
+) -> Result<Request<()>, Status> {
+    let token = req
+        .metadata()
+        .get("authorization")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .ok_or_else(|| Status::unauthenticated("missing bearer token"))?;
+
+    keys.verify(token, account_id)
+        .map_err(|e| Status::unauthenticated(e.to_string()))?;
+
+    Ok(req)
+}
+
+struct BotService {
+    state: ApiState,
+}
+
+impl BotService {
+    async fn send(&self, command: BotCommand) -> Result<(), Status> {
+        self.state
+            .commands
+            .send(command)
+            .await
+            .map_err(|_| Status::unavailable("bot is not running"))
+    }
+
+    /// whether the latest overview lists a planet or moon of `planet_id`
+    async fn knows(&self, planet_id: &str) -> bool {
+        self.state
+            .overview
+            .read()
+            .await
+            .as_ref()
+            .is_some_and(|latest| {
+                latest
+                    .empire_overview
+                    .bodies()
+                    .any(|body| body.id == planet_id)
+            })
+    }
+}
+
+#[tonic::async_trait]
+impl Bot for BotService {
+    async fn get_empire(&self, _: Request<GetEmpireRequest>) -> Result<Response<Empire>, Status> {
+        let latest = self.state.overview.read().await;
+        let latest = latest
+            .as_ref()
+            .ok_or_else(|| Status::not_found("empire overview is not refreshed yet"))?;
+
+        Ok(Response::new(Empire {
+            refreshed_at: Some(timestamp(latest.refreshed_at)),
+            planets: latest.empire_overview.overview.iter().map(planet).collect(),
+            technology: levels(&latest.empire_overview.technology),
+        }))
+    }
+
+    async fn get_status(
+        &self,
+        _: Request<GetStatusRequest>,
+    ) -> Result<Response<BotStatus>, Status> {
+        let status = self.state.status.borrow().clone();
+
+        Ok(Response::new(bot_status(&status)))
+    }
+
+    async fn list_fleet_events(
+        &self,
+        _: Request<ListFleetEventsRequest>,
+    ) -> Result<Response<ListFleetEventsResponse>, Status> {
+        let events = open_fleet_events(&self.state.pg_pool, self.state.account_id.0)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(Response::new(ListFleetEventsResponse {
+            events: events.iter().map(fleet_event).collect(),
+        }))
+    }
+
+    type SubscribeEventsStream = Pin<Box<dyn Stream<Item = Result<BotEvent, Status>> + Send>>;
+
+    async fn subscribe_events(
+        &self,
+        _: Request<SubscribeEventsRequest>,
+    ) -> Result<Response<Self::SubscribeEventsStream>, Status> {
+        let stream = stream::unfold(self.state.events.subscribe(), |mut rx| async move {
+            loop {
+                match rx.recv().await {
+                    Ok(event) => return Some((Ok(bot_event(&event)), rx)),
+                    Err(RecvError::Lagged(skipped)) => {
+                        log::warn!("grpc event stream lagged, {} events skipped", skipped);
+                    }
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        });
+
+        Ok(Response::new(Box::pin(stream)))
+    }
+
+    async fn pause(&self, _: Request<PauseRequest>) -> Result<Response<PauseResponse>, Status> {
+        self.send(BotCommand::Pause).await?;
+
+        Ok(Response::new(PauseResponse {}))
+    }
+
+    async fn resume(&self, _: Request<ResumeRequest>) -> Result<Response<ResumeResponse>, Status> {
+        self.send(BotCommand::Resume).await?;
+
+        Ok(Response::new(ResumeResponse {}))
+    }
+
+    async fn refresh(
+        &self,
+        _: Request<RefreshRequest>,
+    ) -> Result<Response<RefreshResponse>, Status> {
+        self.send(BotCommand::RefreshNow).await?;
+
+        Ok(Response::new(RefreshResponse {}))
+    }
+
+    async fn fleet_save(
+        &self,
+        request: Request<FleetSaveRequest>,
+    ) -> Result<Response<FleetSaveResponse>, Status> {
+        let planet_id = request.into_inner().planet_id;
+        if !self.knows(&planet_id).await {
+            return Err(Status::not_found("planet or moon not found"));
+        }
+
+        let (reply, result) = oneshot::channel();
+        self.send(BotCommand::FleetSave { planet_id, reply })
+            .await?;
+
+        result
+            .await
+            .map_err(|_| Status::unavailable("bot stopped before saving the fleet"))?
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(Response::new(FleetSaveResponse {}))
+    }
+
+    async fn dispatch(
+        &self,
+        request: Request<DispatchRequest>,
+    ) -> Result<Response<DispatchResponse>, Status> {
+        let request = request.into_inner();
+        if !self.knows(&request.planet_id).await {
+            return Err(Status::not_found("planet or moon not found"));
+        }
+        let target = request
+            .target
+            .parse()
+            .map_err(|e: anyhow::Error| Status::invalid_argument(e.to_string()))?;
+        let speed = u8::try_from(request.speed)
+            .ok()
+            .filter(|speed| (1..=10).contains(speed))
+            .ok_or_else(|| Status::invalid_argument("speed is not between 1 and 10"))?;
+        let dispatch = Dispatch {
+            target,
+            mission: request.mission,
+            speed,
+            ships: request.ships.into_iter().collect(),
+            cargo: Load {
+                metal: request.metal,
+                crystal: request.crystal,
+                deuterium: request.deuterium,
+            },
+        };
+
+        let (reply, result) = oneshot::channel();
+        self.send(BotCommand::Dispatch {
+            planet_id: request.planet_id,
+            dispatch,
+            reply,
+        })
+        .await?;
+
+        result
+            .await
+            .map_err(|_| Status::unavailable("bot stopped before dispatching the fleet"))?
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(Response::new(DispatchResponse {}))
+    }
+}
+
+fn timestamp(time: DateTime<Utc>) -> Timestamp {
+    Timestamp {
+        seconds: time.timestamp(),
+        nanos: time.timestamp_subsec_nanos() as i32,
+    }
+}
+
+/// levels or unit amounts keyed by their camelCase field name
+fn levels<T: Serialize>(value: &T) -> HashMap<String, u32> {
+    match serde_json::to_value(value) {
+        Ok(serde_json::Value::Object(fields)) => fields
+            .into_iter()
+            .filter_map(|(name, level)| Some((name, u32::try_from(level.as_u64()?).ok()?)))
+            .collect(),
+        _ => HashMap::new(),
+    }
+}
+
+fn resource(resource: &Resource) -> ogc_proto::Resource {
+    ogc_proto::Resource {
+        metal: resource.metal.0,
+        crystal: resource.crystal.0,
+        deuterium: resource.deuterium.0,
+        energy: resource.energy.0,
+    }
+}
+
+fn planet(planet: &PlanetOverview) -> Planet {
+    Planet {
+        id: planet.id.clone(),
+        location: planet.location.to_string(),
+        resource: Some(resource(&planet.resource)),
+        infrastructure: levels(&planet.infrastructure),
+        facility: levels(&planet.facility),
+        defence: levels(&planet.defence),
+        fleet: levels(&planet.fleet),
+        moon: planet.lunar.as_ref().map(moon),
+    }
+}
+
+fn moon(lunar: &Lunar) -> Moon {
+    Moon {
+        id: lunar.id.clone(),
+        location: lunar.location.to_string(),
+        resource: Some(resource(&lunar.resource)),
+        facility: levels(&lunar.facility),
+        fleet: levels(&lunar.fleet),
+    }
+}
+
+fn fleet_event(record: &FleetEventRecord) -> FleetEvent {
+    FleetEvent {
+        id: record.id,
+        mission_type: record.event.mission_type.to_string(),
+        arrival_time: Some(timestamp(record.event.arrival_time.with_timezone(&Utc))),
+        coords_origin: record.event.coords_origin.to_string(),
+        dest_coords: record.event.dest_coords.to_string(),
+        first_seen_at: Some(timestamp(record.first_seen_at)),
+        last_seen_at: Some(timestamp(record.last_seen_at)),
+        resolved_at: record.resolved_at.map(timestamp),
+    }
+}
+
+fn bot_status(status: &bot::BotStatus) -> BotStatus {
+    let state = match status.state {
+        bot::BotState::LoggingIn => BotState::LoggingIn,
+        bot::BotState::Refreshing => BotState::Refreshing,
+        bot::BotState::FleetSaving => BotState::FleetSaving,
+        bot::BotState::Sleeping => BotState::Sleeping,
+        bot::BotState::Paused => BotState::Paused,
+    };
+
+    BotStatus {
+        state: state.into(),
+        changed_at: Some(timestamp(status.changed_at)),
+        next_refresh_at: status.next_refresh_at.map(timestamp),
+    }
+}
+
+fn bot_event(event: &bot::BotEvent) -> BotEvent {
+    let event = match event {
+        bot::BotEvent::NewFleetEvent(record) => Event::NewFleetEvent(fleet_event(record)),
+        bot::BotEvent::ResolvedFleetEvent(record) => Event::ResolvedFleetEvent(fleet_event(record)),
+        bot::BotEvent::FleetSaved {
+            planet_id,
+            location,
+            saved_at,
+        } => Event::FleetSaved(FleetSaved {
+            planet_id: planet_id.clone(),
+            location: location.to_string(),
+            saved_at: Some(timestamp(*saved_at)),
+        }),
+        bot::BotEvent::FleetRecalled {
+            planet_id,
+            location,
+            recalled_at,
+        } => Event::FleetRecalled(FleetRecalled {
+            planet_id: planet_id.clone(),
+            location: location.to_string(),
+            recalled_at: Some(timestamp(*recalled_at)),
+        }),
+        bot::BotEvent::StateChanged(status) => Event::StateChanged(bot_status(status)),
+    };
+
+    BotEvent { event: Some(event) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        api::tests::{api_state, token, until_state},
+        mono::{
+            bot::BotState,
+            fleet_save::FleetSaveConfig,
+            mock::{home_game, spawn_bot, MockBot, LOCATION},
+        },
+    };
+
+    use std::{collections::BTreeMap, sync::Mutex};
+
+    use ogc_core::model::mission;
+    use ogc_proto::bot_client::BotClient;
+    use tonic::{transport::Channel, Code};
+
+    /// serve the bot service for `bot` on a free port and connect to it
+    async fn connect(bot: &MockBot) -> anyhow::Result<BotClient<Channel>> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        tokio::spawn(serve_on(listener, api_state(bot)));
+
+        Ok(BotClient::connect(format!("http://{}", addr)).await?)
+    }
+
+    fn authorized<T>(message: T, token: &str) -> Request<T> {
+        let mut request = Request::new(message);
+        let value = format!("Bearer {}", token).parse().expect("metadata value");
+        request.metadata_mut().insert("authorization", value);
+
+        request
+    }
+
+    #[actix_web::test]
+    async fn rejects_missing_or_foreign_token() -> anyhow::Result<()> {
+        let game = Arc::new(Mutex::new(home_game()));
+        let bot = spawn_bot(game, FleetSaveConfig::default()).await?;
+        let mut client = connect(&bot).await?;
+
+        let missing = client
+            .get_empire(GetEmpireRequest {})
+            .await
+            .expect_err("no token");
+        assert_eq!(missing.code(), Code::Unauthenticated);
+        let foreign = client
+            .get_empire(authorized(GetEmpireRequest {}, &token(2, Utc::now())))
+            .await
+            .expect_err("token of another account");
+        assert_eq!(foreign.code(), Code::Unauthenticated);
+        bot.stop().await;
+
+        Ok(())
+    }
+
+    #[actix_web::test]
+    async fn serves_empire_and_sends_fleets() -> anyhow::Result<()> {
+        let game = Arc::new(Mutex::new(home_game()));
+        let mut bot = spawn_bot(game.clone(), FleetSaveConfig::default()).await?;
+        let mut client = connect(&bot).await?;
+        let token = token(1, Utc::now());
+        until_state(&mut bot, BotState::Sleeping).await?;
+
+        let empire = client
+            .get_empire(authorized(GetEmpireRequest {}, &token))
+            .await?
+            .into_inner();
+        assert_eq!(empire.planets.len(), 1);
+        assert_eq!(empire.planets[0].id, "1001");
+        assert_eq!(empire.planets[0].fleet["lightFighter"], 100);
+        assert_eq!(
+            empire.planets[0].moon.as_ref().map(|moon| moon.id.as_str()),
+            Some("1002")
+        );
+
+        let deploy = DispatchRequest {
+            planet_id: "1002".to_owned(),
+            target: LOCATION.to_string(),
+            mission: mission::DEPLOYMENT,
+            speed: 5,
+            ships: HashMap::from([(202, 2)]),
+            metal: 1_000,
+            crystal: 0,
+            deuterium: 0,
+        };
+        let unparsable = DispatchRequest {
+            target: "[1:2]".to_owned(),
+            ..deploy.clone()
+        };
+        let status = client
+            .dispatch(authorized(unparsable, &token))
+            .await
+            .expect_err("unparsable target");
+        assert_eq!(status.code(), Code::InvalidArgument);
+        client.dispatch(authorized(deploy, &token)).await?;
+
+        let missing = client
+            .fleet_save(authorized(
+                FleetSaveRequest {
+                    planet_id: "9999".to_owned(),
+                },
+                &token,
+            ))
+            .await
+            .expect_err("unknown planet");
+        assert_eq!(missing.code(), Code::NotFound);
+        client
+            .fleet_save(authorized(
+                FleetSaveRequest {
+                    planet_id: "1001".to_owned(),
+                },
+                &token,
+            ))
+            .await?;
+        bot.stop().await;
+
+        let game = game.lock().expect("mock game lock");
+        assert_eq!(game.dispatches.len(), 2);
+        let deployed = &game.dispatches[0];
+        assert_eq!(deployed.from, "1002");
+        assert_eq!(deployed.target_type, "1");
+        assert_eq!(deployed.mission, mission::DEPLOYMENT.to_string());
+        assert_eq!(deployed.speed, "5");
+        assert_eq!(deployed.ships, BTreeMap::from([(202, 2)]));
+        assert_eq!(deployed.metal, 1_000);
+        assert_eq!(game.dispatches[1].from, "1001");
+
+        Ok(())
+    }
+}
@@ -1,4 +1,7 @@
+mod api;
 mod cli;
+mod grpc;
+mod metrics;
 mod mono;
 
 use clap::Parser;
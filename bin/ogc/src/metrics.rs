@@ -0,0 +1,163 @@
+use std::{future::Future, time::Instant};
+
+use ogc_core::model::{EmpireOverview, MissionType, Resource};
+
+use actix_web::HttpResponse;
+use fantoccini::error::CmdError;
+use once_cell::sync::Lazy;
+use prometheus::{
+    register_histogram, register_histogram_vec, register_int_counter, register_int_counter_vec,
+    register_int_gauge_vec, Encoder, Histogram, HistogramVec, IntCounter, IntCounterVec,
+    IntGaugeVec, TextEncoder,
+};
+
+/// scraping every planet takes minutes, so the buckets reach far beyond the defaults
+const REFRESH_BUCKETS: &[f64] = &[10.0, 30.0, 60.0, 120.0, 180.0, 300.0, 600.0, 900.0];
+
+static REFRESH_DURATION: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        "ogc_refresh_duration_seconds",
+        "duration of a whole refresh cycle",
+        REFRESH_BUCKETS.to_vec()
+    )
+    .expect("register ogc_refresh_duration_seconds")
+});
+
+static SCRAPE_DURATION: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "ogc_scrape_duration_seconds",
+        "duration of a single scraper",
+        &["scraper"]
+    )
+    .expect("register ogc_scrape_duration_seconds")
+});
+
+static SCRAPE_FAILURES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "ogc_scrape_failures_total",
+        "failed scrapes by scraper",
+        &["scraper"]
+    )
+    .expect("register ogc_scrape_failures_total")
+});
+
+static WEBDRIVER_ERRORS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "ogc_webdriver_errors_total",
+        "webdriver or http errors which stopped a login, refresh or fleet save"
+    )
+    .expect("register ogc_webdriver_errors_total")
+});
+
+static FLEET_EVENTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "ogc_fleet_events_total",
+        "new fleet events seen by mission type",
+        &["mission_type"]
+    )
+    .expect("register ogc_fleet_events_total")
+});
+
+static FLEET_SAVES: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!("ogc_fleet_saves_total", "fleet saves dispatched")
+        .expect("register ogc_fleet_saves_total")
+});
+
+static RESOURCES: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "ogc_resource",
+        "resources of a planet or moon in the latest refresh",
+        &["location", "kind", "resource"]
+    )
+    .expect("register ogc_resource")
+});
+
+/// register every metric, so that the untouched ones are exported as zero
+pub fn init() {
+    Lazy::force(&REFRESH_DURATION);
+    Lazy::force(&SCRAPE_DURATION);
+    Lazy::force(&SCRAPE_FAILURES);
+    Lazy::force(&WEBDRIVER_ERRORS);
+    Lazy::force(&FLEET_EVENTS);
+    Lazy::force(&FLEET_SAVES);
+    Lazy::force(&RESOURCES);
+}
+
+/// time a scraper and count its failure
+pub async fn scrape<T>(
+    scraper: &'static str,
+    future: impl Future<Output = anyhow::Result<T>>,
+) -> anyhow::Result<T> {
+    let timer = SCRAPE_DURATION.with_label_values(&[scraper]).start_timer();
+    let result = future.await;
+    timer.observe_duration();
+
+    if result.is_err() {
+        SCRAPE_FAILURES.with_label_values(&[scraper]).inc();
+    }
+
+    result
+}
+
+/// count the error if it was raised by the webdriver or the http client, meant for `map_err`
+pub fn webdriver_error(e: anyhow::Error) -> anyhow::Error {
+    if e.downcast_ref::<CmdError>().is_some() || e.downcast_ref::<reqwest::Error>().is_some() {
+        WEBDRIVER_ERRORS.inc();
+    }
+
+    e
+}
+
+pub fn observe_refresh(started_at: Instant) {
+    REFRESH_DURATION.observe(started_at.elapsed().as_secs_f64());
+}
+
+pub fn observe_fleet_event(mission_type: &MissionType) {
+    FLEET_EVENTS
+        .with_label_values(&[&mission_type.to_string()])
+        .inc();
+}
+
+pub fn observe_fleet_save() {
+    FLEET_SAVES.inc();
+}
+
+pub fn observe_overview(empire_overview: &EmpireOverview) {
+    for planet in empire_overview.overview.iter() {
+        set_resource(&planet.location.to_string(), "planet", &planet.resource);
+        // the kind tells a moon apart, its location label is the one of its planet
+        if let Some(lunar) = &planet.lunar {
+            set_resource(
+                &lunar.location.planet().to_string(),
+                "moon",
+                &lunar.resource,
+            );
+        }
+    }
+}
+
+fn set_resource(location: &str, kind: &str, resource: &Resource) {
+    for (name, amount) in [
+        ("metal", resource.metal),
+        ("crystal", resource.crystal),
+        ("deuterium", resource.deuterium),
+        ("energy", resource.energy),
+    ] {
+        RESOURCES
+            .with_label_values(&[location, kind, name])
+            .set(amount.0);
+    }
+}
+
+/// every metric in the prometheus text format
+pub async fn serve() -> HttpResponse {
+    let mut buffer = Vec::new();
+    if let Err(e) = TextEncoder::new().encode(&prometheus::gather(), &mut buffer) {
+        log::error!("encode metrics error: {}", e);
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    HttpResponse::Ok()
+        .content_type(prometheus::TEXT_FORMAT)
+        .body(buffer)
+}
@@ -1,13 +1,52 @@
-use std::{convert::TryFrom, fs, path::PathBuf};
+use crate::{
+    metrics,
+    mono::{
+        dispatch::Dispatch,
+        fleet_save::{FleetSaveConfig, FleetSaveStrategy},
+        transport::{Server, Tab, Transport, TransportKind},
+    },
+};
+
+use std::{fs, path::PathBuf, sync::Arc, time::Instant};
+
+use ogc_core::{
+    coordinates::Coordinates,
+    model::{
+        Defence, EmpireOverview, Fleet, FleetEvent, Infrastructure, Lunar, LunarFacility,
+        MissionType, PlanetFacility, PlanetOverview, Resource, Technology,
+    },
+    parser::{
+        empire::{parse_empire, EmpireEntry},
+        fleet_event::parse_fleet_events,
+        html::Page,
+        movement::{parse_movement, FleetMovement},
+        planet::{
+            parse_defence, parse_fleet, parse_infrastructure, parse_location, parse_lunar_facility,
+            parse_planet_facility, parse_resource,
+        },
+        planet_list::{parse_planet_list, PlanetListEntry},
+        technology::parse_technology,
+    },
+    selector::Selectors,
+    sqlx_postgres::{
+        empire::save_empire_overview,
+        fleet_event::{record_fleet_events, FleetEventRecord},
+        saved_fleet::{
+            close_saved_fleet, insert_saved_fleet, open_saved_fleets, resolve_saved_fleet,
+            SavedFleet,
+        },
+        sqlx::PgPool,
+    },
+};
 
 use anyhow::anyhow;
-use chrono::{DateTime, Local, TimeZone, Utc};
-use fantoccini::{elements::Element, Client, ClientBuilder, Locator};
+use chrono::{DateTime, Duration, FixedOffset, Local, TimeZone, Utc};
 use rand::Rng;
-use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::{Deserialize, Serialize};
+use strum_macros::IntoStaticStr;
 use tokio::{
-    sync::{Mutex, RwLock},
-    time::{sleep, Duration},
+    sync::{broadcast, mpsc, oneshot, watch, RwLock},
+    time::sleep,
 };
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -15,280 +54,889 @@ use tokio::{
 pub struct User {
     pub account: String,
     pub password: String,
+    /// scopes the persisted data, defaults to the universe of the server, e.g. `s144-tw`
+    #[serde(default)]
+    pub universe: String,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct Config {
     pub user: User,
+    #[serde(default)]
+    pub server: Server,
     pub planets_info: PlanetsInfo,
+    /// laid over the embedded selector manifest, e.g. `[selectors.menu]`
+    #[serde(default)]
+    pub selectors: Option<toml::value::Table>,
+    #[serde(default)]
+    pub scrape_mode: ScrapeMode,
+    #[serde(default)]
+    pub transport: TransportKind,
+    #[serde(default)]
+    pub fleet_save: FleetSaveConfig,
+}
+
+/// how the empire overview is scraped
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ScrapeMode {
+    /// click through the tabs of every planet and moon
+    #[default]
+    Tabs,
+    /// read the data embedded in the empire page, one page load for planets and one for moons
+    Empire,
 }
 
 impl Config {
     pub fn load(file_path: &PathBuf) -> anyhow::Result<Self> {
         let config_string = fs::read_to_string(file_path)?;
-        Ok(toml::from_str(&config_string)?)
+        let mut config: Self = toml::from_str(&config_string)?;
+        if config.user.universe.is_empty() {
+            config.user.universe = config.server.universe();
+        }
+        config.server.timezone()?;
+        config.fleet_save.validate()?;
+
+        Ok(config)
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+/// the planets and moons are read from the planet list, listing a planet here excludes or
+/// tags it, without a planet list the listed planets are scraped
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct PlanetsInfo {
+    #[serde(default)]
     planets: Vec<PlanetId>,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct PlanetId {
     planet_id: String,
     lunar_id: Option<String>,
+    /// leave the planet and its moon alone
+    #[serde(default)]
+    exclude: bool,
+    /// shown next to the planet in the logs, e.g. `main`
+    #[serde(default)]
+    tag: Option<String>,
 }
 
-#[derive(Clone, Debug)]
-pub struct CheatBot {
-    pub client: Client,
-    pub planets_info: PlanetsInfo,
+impl PlanetId {
+    /// the id with its tag, e.g. `33630196 (main)`
+    fn label(&self) -> String {
+        match &self.tag {
+            Some(tag) => format!("{} ({})", self.planet_id, tag),
+            None => self.planet_id.clone(),
+        }
+    }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-#[serde(rename_all = "camelCase")]
-pub struct Resource {
-    pub metal: String,
-    pub crystal: String,
-    pub deuterium: String,
-    pub energy: String,
+impl PlanetsInfo {
+    fn configured(&self, planet_id: &str) -> Option<&PlanetId> {
+        self.planets
+            .iter()
+            .find(|planet| planet.planet_id == planet_id)
+    }
+
+    /// the planets to scrape, `planet_list` is none if it could not be read
+    fn resolve(&self, planet_list: Option<&[PlanetListEntry]>) -> Vec<PlanetId> {
+        let planets = match planet_list {
+            Some(planet_list) => planet_list
+                .iter()
+                .map(|entry| {
+                    let configured = self.configured(&entry.planet_id);
+                    PlanetId {
+                        planet_id: entry.planet_id.clone(),
+                        lunar_id: entry.lunar_id.clone(),
+                        exclude: configured.is_some_and(|planet| planet.exclude),
+                        tag: configured.and_then(|planet| planet.tag.clone()),
+                    }
+                })
+                .collect(),
+            None => self.planets.clone(),
+        };
+
+        planets
+            .into_iter()
+            .filter(|planet| !planet.exclude)
+            .collect()
+    }
+
+    /// warn about every planet or moon the config and the planet list disagree on
+    fn warn_mismatch(&self, planet_list: &[PlanetListEntry]) {
+        for planet in self.planets.iter() {
+            match planet_list
+                .iter()
+                .find(|entry| entry.planet_id == planet.planet_id)
+            {
+                None => log::warn!(
+                    "planet {} is configured but not in the planet list",
+                    planet.label()
+                ),
+                Some(entry) if planet.lunar_id.is_some() && entry.lunar_id != planet.lunar_id => {
+                    log::warn!(
+                        "planet {} is configured with moon {:?}, the planet list shows {:?}",
+                        planet.label(),
+                        planet.lunar_id,
+                        entry.lunar_id
+                    )
+                }
+                Some(_) => {}
+            }
+        }
+        if self.planets.is_empty() {
+            return;
+        }
+        for entry in planet_list.iter() {
+            if self.configured(&entry.planet_id).is_none() {
+                log::warn!(
+                    "planet {} is not configured, it is scraped as found in the planet list",
+                    entry.planet_id
+                );
+            }
+        }
+    }
 }
 
+/// the empire overview of the latest refresh
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
-pub struct Infrastructure {
-    pub metal_mine: u32,
-    pub crystal_mine: u32,
-    pub deuterium_synthesizer: u32,
-    pub energy_plant: u32,
-    pub fusion_reactor: u32,
-    pub solar_satellite: u32,
-    pub crawler: u32,
-    pub metal_storage: u32,
-    pub crystal_storage: u32,
-    pub deuterium_tank: u32,
+pub struct LatestOverview {
+    pub refreshed_at: DateTime<Utc>,
+    pub empire_overview: EmpireOverview,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+/// latest overview shared between the bot and the http service
+pub type SharedOverview = Arc<RwLock<Option<LatestOverview>>>;
+
+/// what the bot loop is doing
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
-pub struct PlanetFacility {
-    pub robotics_factory: u32,
-    pub shipyard: u32,
-    pub research_lab: u32,
-    pub alliance_depot: u32,
-    pub missile_silo: u32,
-    pub nanite_factory: u32,
-    pub terraformer: u32,
-    pub space_dock: u32,
+pub enum BotState {
+    LoggingIn,
+    Refreshing,
+    FleetSaving,
+    Sleeping,
+    Paused,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
-pub struct LunarFacility {
-    pub robotics_factory: u32,
-    pub shipyard: u32,
-    pub lunar_base: u32,
-    pub sensor_phalanx: u32,
-    pub jump_gate: u32,
+pub struct BotStatus {
+    pub state: BotState,
+    pub changed_at: DateTime<Utc>,
+    /// set while sleeping until the next refresh
+    pub next_refresh_at: Option<DateTime<Utc>>,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-#[serde(rename_all = "camelCase")]
-pub struct Technology {
-    pub energy_technology: u32,
-    pub laser_technology: u32,
-    pub ion_technology: u32,
-    pub hyperspace_technology: u32,
-    pub plasma_technology: u32,
-    pub combustion_drive: u32,
-    pub impulse_drive: u32,
-    pub hyperspace_drive: u32,
-    // pub espionage_technology: u32,
-    pub espionage_technology: String,
-    pub computer_technology: u32,
-    pub astrophysics: u32,
-    pub intergalactic_research_network: u32,
-    pub graviton_technology: u32,
-    pub armour_technology: u32,
-    pub weapons_technology: u32,
-    pub shielding_technology: u32,
+/// latest status of the bot loop, the http service holds a receiver
+pub type BotStatusSender = Arc<watch::Sender<BotStatus>>;
+
+/// commands served by the bot loop between refreshes
+#[derive(Debug)]
+pub enum BotCommand {
+    /// stop refreshing until resumed
+    Pause,
+    Resume,
+    /// refresh immediately, a paused bot stays paused afterwards
+    RefreshNow,
+    /// send the fleet of a planet away, replying once dispatched
+    FleetSave {
+        planet_id: String,
+        reply: oneshot::Sender<anyhow::Result<()>>,
+    },
+    /// send a fleet of a planet or moon as commanded, replying once dispatched
+    Dispatch {
+        planet_id: String,
+        dispatch: Dispatch,
+        reply: oneshot::Sender<anyhow::Result<()>>,
+    },
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-#[serde(rename_all = "camelCase")]
-pub struct Defence {
-    pub rocket_launcher: u32,
-    pub light_laser: u32,
-    pub heavy_laser: u32,
-    pub ion_cannon: u32,
-    pub gauss_cannon: u32,
-    pub plasma_turret: u32,
-    pub small_shield_dome: u32,
-    pub large_shield_dome: u32,
-    pub anti_ballistic_missile: u32,
-    pub interplanetary_missile: u32,
+/// pushed to the http service as it happens in the bot loop
+#[derive(Serialize, Clone, Debug, IntoStaticStr)]
+#[serde(tag = "type", content = "data", rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum BotEvent {
+    NewFleetEvent(FleetEventRecord),
+    ResolvedFleetEvent(FleetEventRecord),
+    #[serde(rename_all = "camelCase")]
+    FleetSaved {
+        planet_id: String,
+        location: Coordinates,
+        saved_at: DateTime<Utc>,
+    },
+    #[serde(rename_all = "camelCase")]
+    FleetRecalled {
+        planet_id: String,
+        location: Coordinates,
+        recalled_at: DateTime<Utc>,
+    },
+    StateChanged(BotStatus),
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, Default)]
-#[serde(rename_all = "camelCase")]
-pub struct Fleet {
-    pub light_fighter: u32,
-    pub heavy_fighter: u32,
-    pub cruiser: u32,
-    pub battleship: u32,
-    pub battlecruiser: u32,
-    pub bomber: u32,
-    pub destroyer: u32,
-    pub deathstar: u32,
-    pub reaper: u32,
-    pub pathfinder: u32,
-    pub small_cargo_ship: u32,
-    pub large_cargo_ship: u32,
-    pub colony_ship: u32,
-    pub recycler: u32,
-    pub espionage_probe: u32,
+/// sender of the bot events, every http subscriber holds a receiver
+pub type BotEvents = broadcast::Sender<BotEvent>;
+
+/// state the bot shares with the api
+#[derive(Clone, Debug)]
+pub struct BotHandles {
+    pub overview: SharedOverview,
+    pub events: BotEvents,
+    pub status: BotStatusSender,
 }
 
-impl Fleet {
-    pub fn is_zero(&self) -> bool {
-        let sum = self.light_fighter
-            + self.heavy_fighter
-            + self.cruiser
-            + self.battleship
-            + self.battlecruiser
-            + self.bomber
-            + self.destroyer
-            + self.deathstar
-            + self.reaper
-            + self.pathfinder
-            + self.small_cargo_ship
-            + self.large_cargo_ship
-            + self.colony_ship
-            + self.recycler
-            + self.espionage_probe;
-
-        sum == 0
-    }
+/// how the bot plays, taken from the config
+#[derive(Clone, Debug)]
+pub struct BotSettings {
+    pub scrape_mode: ScrapeMode,
+    pub fleet_save: FleetSaveConfig,
+    /// of the server clock
+    pub timezone: FixedOffset,
+}
 
-    pub fn is_not_zero(&self) -> bool {
-        let sum = self.light_fighter
-            + self.heavy_fighter
-            + self.cruiser
-            + self.battleship
-            + self.battlecruiser
-            + self.bomber
-            + self.destroyer
-            + self.deathstar
-            + self.reaper
-            + self.pathfinder
-            + self.small_cargo_ship
-            + self.large_cargo_ship
-            + self.colony_ship
-            + self.recycler
-            + self.espionage_probe;
-
-        sum != 0
-    }
+/// a fleet save waiting until the attack is a margin before impact
+#[derive(Clone, Debug)]
+struct ScheduledSave {
+    event: FleetEvent,
+    planet_id: String,
+    location: Coordinates,
+    save_at: DateTime<Utc>,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-#[serde(rename_all = "camelCase")]
-pub struct EmpireOverview {
-    pub overview: Vec<PlanetOverview>,
-    technology: Technology,
-    pub maybe_fleet_events: Option<Vec<FleetEvent>>,
+/// a saved fleet waiting to be recalled, `id` is none if it could not be persisted
+#[derive(Clone, Debug)]
+struct TrackedFleet {
+    id: Option<i64>,
+    fleet: SavedFleet,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-#[serde(rename_all = "camelCase")]
-pub struct PlanetOverview {
-    pub id: String,
-    pub location: String,
-    resource: Resource,
-    infrastructure: Infrastructure,
-    facility: PlanetFacility,
-    defence: Defence,
-    pub fleet: Fleet,
-    lunar: Option<Lunar>,
+impl TrackedFleet {
+    /// when the recall is due, a fleet of an attack still listed is checked once it landed
+    fn recall_at(&self, delay: Duration) -> DateTime<Utc> {
+        self.fleet
+            .resolved_at
+            .unwrap_or_else(|| self.fleet.attack.arrival_time.with_timezone(&Utc))
+            + delay
+    }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-#[serde(rename_all = "camelCase")]
-pub struct Lunar {
-    id: String,
-    location: String,
-    resource: Resource,
-    facility: LunarFacility,
-    pub fleet: Fleet,
+/// why the bot stopped waiting
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Wake {
+    Refresh,
+    /// a fleet save or a recall is due
+    Timer,
+}
+
+#[derive(Clone, Debug)]
+pub struct CheatBot {
+    pub transport: Arc<dyn Transport>,
+    pub planets_info: PlanetsInfo,
+    pub selectors: Arc<Selectors>,
+    pub scrape_mode: ScrapeMode,
+    pub fleet_save: FleetSaveConfig,
+    pub timezone: FixedOffset,
+    pub pg_pool: PgPool,
+    pub account_id: i32,
+    pub overview: SharedOverview,
+    pub events: BotEvents,
+    pub status: BotStatusSender,
 }
 
 impl CheatBot {
     /// create a CheaterBot instance
-    pub async fn new(
-        web_driver_url: Option<&str>,
+    pub fn new(
+        transport: Arc<dyn Transport>,
         planets_info: PlanetsInfo,
-    ) -> anyhow::Result<Self> {
-        let web_driver_url = web_driver_url.unwrap_or("http://localhost:9515");
-        let client = ClientBuilder::native().connect(web_driver_url).await?;
-
-        Ok(Self {
-            client,
+        selectors: Arc<Selectors>,
+        settings: BotSettings,
+        pg_pool: PgPool,
+        account_id: i32,
+        handles: BotHandles,
+    ) -> Self {
+        Self {
+            transport,
             planets_info,
-        })
+            selectors,
+            scrape_mode: settings.scrape_mode,
+            fleet_save: settings.fleet_save,
+            timezone: settings.timezone,
+            pg_pool,
+            account_id,
+            overview: handles.overview,
+            events: handles.events,
+            status: handles.status,
+        }
+    }
+
+    /// publish an event, nobody listening is not an error
+    fn emit(&self, event: BotEvent) {
+        let _ = self.events.send(event);
     }
 
-    pub async fn start(&self, account: &str, password: &str) -> anyhow::Result<()> {
-        self.login(account, password).await?;
+    fn emit_state(&self, state: BotState, next_refresh_at: Option<DateTime<Utc>>) {
+        let status = BotStatus {
+            state,
+            changed_at: Utc::now(),
+            next_refresh_at,
+        };
+        self.status.send_replace(status.clone());
+        self.emit(BotEvent::StateChanged(status));
+    }
 
+    pub async fn start(
+        &self,
+        account: &str,
+        password: &str,
+        mut commands: mpsc::Receiver<BotCommand>,
+    ) -> anyhow::Result<()> {
+        self.emit_state(BotState::LoggingIn, None);
+        self.transport
+            .login(account, password)
+            .await
+            .map_err(metrics::webdriver_error)?;
+
+        let mut paused = false;
+        let mut planet_list = None;
+        let mut scheduled = Vec::new();
+        let mut tracked = self.load_saved_fleets().await;
         loop {
             let expiration = Self::calculate_expiration()?;
+            let refresh_started_at = Instant::now();
+            self.emit_state(BotState::Refreshing, None);
             log::info!(
                 "refreshing game state... {}",
                 Local::now().format("%Y/%m/%d %H:%M:%S")
             );
-            let empire_overview = self.empire_overview().await?;
+            let planets = self.discover_planets(&mut planet_list).await;
+            let empire_overview = self
+                .empire_overview(&planets)
+                .await
+                .map_err(metrics::webdriver_error)?;
+            let refreshed_at = Utc::now();
+            metrics::observe_overview(&empire_overview);
             log::info!("empire_overview {:#?}", empire_overview);
+            *self.overview.write().await = Some(LatestOverview {
+                refreshed_at,
+                empire_overview: empire_overview.clone(),
+            });
+            // keep the latest state in database, a failed write should not stop the bot
+            if let Err(e) = save_empire_overview(
+                &self.pg_pool,
+                self.account_id,
+                &empire_overview,
+                refreshed_at,
+            )
+            .await
+            {
+                log::error!("save empire overview error: {}", e);
+            }
+            // an event missing from an unknown event list is not over
+            if let Some(fleet_events) = &empire_overview.maybe_fleet_events {
+                self.record_fleet_events(fleet_events, refreshed_at).await;
+            }
             // check if is being attack, and do fs
-            if let Some(fleet_events) = empire_overview.maybe_fleet_events {
-                for event in fleet_events.iter() {
-                    if event.mission_type == MissionType::EnemyAttacking {
-                        log::warn!(
-                            "{} is being attacked by {}",
-                            event.dest_coords,
-                            event.coords_origin
-                        );
-
-                        let planet = empire_overview
-                            .overview
-                            .iter()
-                            .find(|planet| planet.location == event.dest_coords)
-                            .ok_or_else(|| anyhow::anyhow!("no planet or lunar match"))?;
+            self.schedule_fleet_saves(&empire_overview, &mut scheduled);
+            self.run_fleet_saves(&mut scheduled, &mut tracked).await;
+            self.recall_fleets(&mut tracked, empire_overview.maybe_fleet_events.as_deref())
+                .await;
+            metrics::observe_refresh(refresh_started_at);
+            log::info!(
+                "next refresh time: {}",
+                expiration.with_timezone(&Local).format("%Y/%m/%d %H:%M:%S")
+            );
 
-                        // check if fleet still on planet, active fleet saveing
-                        if planet.fleet.is_not_zero() {
-                            self.fleet_saving(&planet.id).await?;
-                        }
+            // delay until expiration for refreshing game state, waking up for the fleet saves
+            // and recalls, a recall overdue is left to the next refresh
+            loop {
+                let now = Utc::now();
+                let recall_delay = self.fleet_save.recall.delay();
+                let next_timer = scheduled
+                    .iter()
+                    .map(|save| save.save_at)
+                    .chain(
+                        tracked
+                            .iter()
+                            .map(|fleet| fleet.recall_at(recall_delay))
+                            .filter(|recall_at| *recall_at > now),
+                    )
+                    .min();
+                match self
+                    .wait_for_refresh(expiration, next_timer, &mut paused, &mut commands)
+                    .await?
+                {
+                    Wake::Timer => {
+                        self.run_fleet_saves(&mut scheduled, &mut tracked).await;
+                        self.recall_due_fleets(&mut tracked).await;
                     }
+                    Wake::Refresh => break,
                 }
             }
+        }
+    }
+
+    /// store the events of a refresh, the new and the resolved ones are pushed
+    async fn record_fleet_events(&self, fleet_events: &[FleetEvent], refreshed_at: DateTime<Utc>) {
+        match record_fleet_events(&self.pg_pool, self.account_id, fleet_events, refreshed_at).await
+        {
+            Ok(changes) => {
+                for record in changes.new {
+                    log::info!("new fleet event {:?}", record.event);
+                    metrics::observe_fleet_event(&record.event.mission_type);
+                    self.emit(BotEvent::NewFleetEvent(record));
+                }
+                for record in changes.resolved {
+                    log::info!("resolved fleet event {:?}", record.event);
+                    self.emit(BotEvent::ResolvedFleetEvent(record));
+                }
+            }
+            Err(e) => log::error!("record fleet events error: {}", e),
+        }
+    }
+
+    /// schedule a fleet save for every attack on a planet or moon with a fleet, saves of attacks
+    /// no longer listed are cancelled, an unknown event list leaves them as they are
+    fn schedule_fleet_saves(
+        &self,
+        empire_overview: &EmpireOverview,
+        scheduled: &mut Vec<ScheduledSave>,
+    ) {
+        let Some(fleet_events) = &empire_overview.maybe_fleet_events else {
+            return;
+        };
+        let attacks = fleet_events
+            .iter()
+            .filter(|event| event.mission_type == MissionType::EnemyAttacking)
+            .collect::<Vec<_>>();
+
+        // the attacker recalled or retargeted the fleet
+        scheduled.retain(|save| {
+            let listed = attacks.contains(&&save.event);
+            if !listed {
+                log::info!(
+                    "attack on {} is gone, its fleet save is cancelled",
+                    save.location
+                );
+            }
+            listed
+        });
+
+        for event in attacks {
+            log::warn!(
+                "{} is being attacked by {}, arriving at {}",
+                event.dest_coords,
+                event.coords_origin,
+                event.arrival_time.format("%Y/%m/%d %H:%M:%S")
+            );
+            if scheduled.iter().any(|save| save.event == *event) {
+                continue;
+            }
+
+            // the planet or the moon under attack, each holds its own fleet
+            let Some(body) = empire_overview.body(&event.dest_coords) else {
+                log::warn!(
+                    "{} is not scraped, its fleet is not saved",
+                    event.dest_coords
+                );
+                continue;
+            };
+
+            // check if fleet still on planet or moon, active fleet saveing
+            if body.fleet.is_not_zero() {
+                let save_at = event.arrival_time.with_timezone(&Utc) - self.fleet_save.margin();
+                log::info!(
+                    "fleet of {} is saved at {}",
+                    body.location,
+                    save_at.with_timezone(&Local).format("%Y/%m/%d %H:%M:%S")
+                );
+                scheduled.push(ScheduledSave {
+                    event: event.clone(),
+                    planet_id: body.id.to_owned(),
+                    location: body.location,
+                    save_at,
+                });
+            }
+        }
+    }
+
+    /// save the fleets whose time has come, an attack missing from the event list by then was
+    /// recalled and its fleet stays, a saved fleet is tracked until it is recalled, every due
+    /// fleet is saved if the event list can not be read
+    async fn run_fleet_saves(
+        &self,
+        scheduled: &mut Vec<ScheduledSave>,
+        tracked: &mut Vec<TrackedFleet>,
+    ) {
+        let now = Utc::now();
+        let (due, pending): (Vec<_>, Vec<_>) =
+            scheduled.drain(..).partition(|save| save.save_at <= now);
+        *scheduled = pending;
+        if due.is_empty() {
+            return;
+        }
+
+        // a fleet saved for nothing is recalled, a fleet left behind is lost
+        let events = match metrics::scrape("get_fleet_events", self.get_fleet_events()).await {
+            Ok(events) => Some(events),
+            Err(e) => {
+                log::error!("read fleet events error, saving every due fleet: {}", e);
+                None
+            }
+        };
+        for save in due {
+            if events
+                .as_ref()
+                .is_some_and(|events| !events.contains(&save.event))
+            {
+                log::info!("attack on {} was recalled, its fleet stays", save.location);
+                continue;
+            }
+
+            log::warn!(
+                "attack on {} lands at {}, saving its fleet",
+                save.location,
+                save.event.arrival_time.format("%Y/%m/%d %H:%M:%S")
+            );
+            // the fleet may have left since the refresh, the next attack is answered anyway
+            match self.save_fleet(&save.planet_id, &save.location).await {
+                Ok(strategy) if self.fleet_save.recall.enabled => {
+                    let fleet = SavedFleet {
+                        planet_id: save.planet_id,
+                        location: save.location,
+                        destination: strategy.destination(&save.location),
+                        mission: strategy.mission_id(),
+                        attack: save.event,
+                        saved_at: Utc::now(),
+                        resolved_at: None,
+                    };
+                    tracked.push(self.track_saved_fleet(fleet).await);
+                }
+                Ok(_) => {}
+                Err(e) => log::error!("fleet save of {} error: {}", save.location, e),
+            }
+        }
+    }
+
+    /// the saved fleets left open by a previous run, none if they can not be read or the recall
+    /// is disabled
+    async fn load_saved_fleets(&self) -> Vec<TrackedFleet> {
+        if !self.fleet_save.recall.enabled {
+            return Vec::new();
+        }
+        match open_saved_fleets(&self.pg_pool, self.account_id).await {
+            Ok(records) => {
+                if !records.is_empty() {
+                    log::info!("{} saved fleets waiting to be recalled", records.len());
+                }
+                records
+                    .into_iter()
+                    .map(|record| TrackedFleet {
+                        id: Some(record.id),
+                        fleet: record.fleet,
+                    })
+                    .collect()
+            }
+            Err(e) => {
+                log::error!("load saved fleets error: {}", e);
+                Vec::new()
+            }
+        }
+    }
+
+    /// persist a saved fleet, a failed write only loses it on restart
+    async fn track_saved_fleet(&self, fleet: SavedFleet) -> TrackedFleet {
+        log::info!(
+            "fleet of {} is recalled from {} once the attack is over",
+            fleet.location,
+            fleet.destination
+        );
+        let id = match insert_saved_fleet(&self.pg_pool, self.account_id, &fleet).await {
+            Ok(id) => Some(id),
+            Err(e) => {
+                log::error!("save tracked fleet error: {}", e);
+                None
+            }
+        };
+
+        TrackedFleet { id, fleet }
+    }
+
+    /// read the event list for the recalls that are due, they are retried on the next refresh if
+    /// it can not be read, a fleet is not recalled into an attack that may still be under way
+    async fn recall_due_fleets(&self, tracked: &mut Vec<TrackedFleet>) {
+        let now = Utc::now();
+        let recall_delay = self.fleet_save.recall.delay();
+        if !tracked
+            .iter()
+            .any(|fleet| fleet.recall_at(recall_delay) <= now)
+        {
+            return;
+        }
+
+        match metrics::scrape("get_fleet_events", self.get_fleet_events()).await {
+            Ok(events) => self.recall_fleets(tracked, Some(&events)).await,
+            Err(e) => log::error!(
+                "read fleet events error, recalls wait for the refresh: {}",
+                e
+            ),
+        }
+    }
+
+    /// a saved fleet is resolved once its attack left `events`, and recalled after the delay,
+    /// unknown events resolve nothing, a fleet no longer under way is not tracked anymore, errors
+    /// are retried on the next call
+    async fn recall_fleets(&self, tracked: &mut Vec<TrackedFleet>, events: Option<&[FleetEvent]>) {
+        let now = Utc::now();
+        for fleet in tracked.iter_mut() {
+            if fleet.fleet.resolved_at.is_some()
+                || events.is_none_or(|events| events.contains(&fleet.fleet.attack))
+            {
+                continue;
+            }
+            // the attack is gone at the latest when it landed
+            let resolved_at = now.min(fleet.fleet.attack.arrival_time.with_timezone(&Utc));
             log::info!(
-                "next refresh time: {}",
-                expiration.with_timezone(&Local).format("%Y/%m/%d %H:%M:%S")
+                "attack on {} is over, its fleet is recalled at {}",
+                fleet.fleet.location,
+                (resolved_at + self.fleet_save.recall.delay())
+                    .with_timezone(&Local)
+                    .format("%Y/%m/%d %H:%M:%S")
             );
+            fleet.fleet.resolved_at = Some(resolved_at);
+            if let Some(id) = fleet.id {
+                if let Err(e) = resolve_saved_fleet(&self.pg_pool, id, resolved_at).await {
+                    log::error!("resolve tracked fleet error: {}", e);
+                }
+            }
+        }
 
-            // delay until expiration for refreshing game state
-            sleep(Duration::from_millis(
-                (expiration.timestamp_millis() - Utc::now().timestamp_millis()) as u64,
-            ))
-            .await;
+        let recall_delay = self.fleet_save.recall.delay();
+        let (due, pending): (Vec<_>, Vec<_>) = tracked.drain(..).partition(|fleet| {
+            fleet.fleet.resolved_at.is_some() && fleet.recall_at(recall_delay) <= now
+        });
+        *tracked = pending;
+        if due.is_empty() {
+            return;
+        }
+
+        let mut movements =
+            match metrics::scrape("get_fleet_movements", self.get_fleet_movements()).await {
+                Ok(movements) => movements,
+                Err(e) => {
+                    log::error!("read fleet movement error: {}", e);
+                    tracked.extend(due);
+                    return;
+                }
+            };
+        for fleet in due {
+            // the fleet recalled is left out, two saves of one planet may fly the same way
+            let Some(index) = movements.iter().position(|movement| {
+                movement.is_recallable()
+                    && movement.mission == fleet.fleet.mission
+                    && movement.origin == fleet.fleet.location
+                    && movement.destination == fleet.fleet.destination
+            }) else {
+                log::info!(
+                    "fleet of {} is no longer under way, it is not recalled",
+                    fleet.fleet.location
+                );
+                self.close_tracked_fleet(&fleet, false).await;
+                continue;
+            };
+            let movement = movements.swap_remove(index);
+
+            match self
+                .transport
+                .recall(&movement.id)
+                .await
+                .map_err(metrics::webdriver_error)
+            {
+                Ok(()) => {
+                    log::warn!(
+                        "fleet of {} recalled from {}",
+                        fleet.fleet.location,
+                        fleet.fleet.destination
+                    );
+                    self.close_tracked_fleet(&fleet, true).await;
+                    self.emit(BotEvent::FleetRecalled {
+                        planet_id: fleet.fleet.planet_id.clone(),
+                        location: fleet.fleet.location,
+                        recalled_at: Utc::now(),
+                    });
+                }
+                Err(e) => {
+                    log::error!("recall fleet of {} error: {}", fleet.fleet.location, e);
+                    tracked.push(fleet);
+                }
+            }
+        }
+    }
+
+    async fn close_tracked_fleet(&self, fleet: &TrackedFleet, recalled: bool) {
+        if let Some(id) = fleet.id {
+            if let Err(e) = close_saved_fleet(&self.pg_pool, id, Utc::now(), recalled).await {
+                log::error!("close tracked fleet error: {}", e);
+            }
         }
     }
 
+    /// sleep until `expiration`, or `next_timer` if earlier, serving commands meanwhile, a paused
+    /// bot does not refresh but still wakes for `next_timer`, so no fleet save is dropped
+    async fn wait_for_refresh(
+        &self,
+        expiration: DateTime<Utc>,
+        next_timer: Option<DateTime<Utc>>,
+        paused: &mut bool,
+        commands: &mut mpsc::Receiver<BotCommand>,
+    ) -> anyhow::Result<Wake> {
+        let (wake_at, wake) = match next_timer {
+            Some(timer_at) if timer_at < expiration => (timer_at, Wake::Timer),
+            _ => (expiration, Wake::Refresh),
+        };
+        loop {
+            if *paused {
+                self.emit_state(BotState::Paused, None);
+            } else {
+                self.emit_state(BotState::Sleeping, Some(expiration));
+            }
+
+            let delay = (wake_at - Utc::now()).to_std().unwrap_or_default();
+            let command = if *paused {
+                match next_timer {
+                    Some(timer_at) => {
+                        let delay = (timer_at - Utc::now()).to_std().unwrap_or_default();
+                        tokio::select! {
+                            _ = sleep(delay) => return Ok(Wake::Timer),
+                            command = commands.recv() => command,
+                        }
+                    }
+                    None => commands.recv().await,
+                }
+            } else {
+                tokio::select! {
+                    _ = sleep(delay) => return Ok(wake),
+                    command = commands.recv() => command,
+                }
+            };
+
+            match command {
+                Some(BotCommand::Pause) => {
+                    log::info!("bot paused");
+                    *paused = true;
+                }
+                Some(BotCommand::Resume) => {
+                    log::info!("bot resumed");
+                    *paused = false;
+                }
+                Some(BotCommand::RefreshNow) => return Ok(Wake::Refresh),
+                Some(BotCommand::FleetSave { planet_id, reply }) => {
+                    let result = self.save_fleet_on_demand(&planet_id).await;
+                    let _ = reply.send(result);
+                }
+                Some(BotCommand::Dispatch {
+                    planet_id,
+                    dispatch,
+                    reply,
+                }) => {
+                    let result = self.dispatch_on_demand(&planet_id, &dispatch).await;
+                    let _ = reply.send(result);
+                }
+                // nobody can send commands anymore, so a paused bot would never resume
+                None if *paused => return Err(anyhow!("command channel closed while paused")),
+                None => {
+                    sleep(delay).await;
+                    return Ok(wake);
+                }
+            }
+        }
+    }
+
+    /// save the fleet of a planet or moon by the first of its strategies the game takes, which
+    /// is returned
+    async fn save_fleet(
+        &self,
+        planet_id: &str,
+        location: &Coordinates,
+    ) -> anyhow::Result<&FleetSaveStrategy> {
+        self.emit_state(BotState::FleetSaving, None);
+        let mut errors = Vec::new();
+        for strategy in self.fleet_save.strategies_of(planet_id) {
+            match self
+                .transport
+                .fleet_save(planet_id, location, strategy)
+                .await
+                .map_err(metrics::webdriver_error)
+            {
+                Ok(()) => {
+                    log::warn!("fleet of {} saved by {}", location, strategy);
+                    metrics::observe_fleet_save();
+                    self.emit(BotEvent::FleetSaved {
+                        planet_id: planet_id.to_owned(),
+                        location: *location,
+                        saved_at: Utc::now(),
+                    });
+                    return Ok(strategy);
+                }
+                Err(e) => {
+                    log::warn!("fleet of {} can not {}: {}", location, strategy, e);
+                    errors.push(format!("{}: {}", strategy, e));
+                }
+            }
+        }
+
+        Err(anyhow!(
+            "no fleet save of {} is available, {}",
+            location,
+            errors.join(", ")
+        ))
+    }
+
+    /// location of a planet or moon in the latest overview
+    async fn location_of(&self, planet_id: &str) -> anyhow::Result<Coordinates> {
+        self.overview
+            .read()
+            .await
+            .as_ref()
+            .and_then(|latest| {
+                latest
+                    .empire_overview
+                    .bodies()
+                    .find(|body| body.id == planet_id)
+                    .map(|body| body.location)
+            })
+            .ok_or_else(|| anyhow!("planet or moon {} not found", planet_id))
+    }
+
+    /// `planet_id` is the id of a planet or a moon
+    async fn save_fleet_on_demand(&self, planet_id: &str) -> anyhow::Result<()> {
+        let location = self.location_of(planet_id).await?;
+
+        log::warn!("fleet saving {} on demand", location);
+        self.save_fleet(planet_id, &location).await?;
+
+        Ok(())
+    }
+
+    /// `planet_id` is the id of a planet or a moon
+    async fn dispatch_on_demand(&self, planet_id: &str, dispatch: &Dispatch) -> anyhow::Result<()> {
+        let location = self.location_of(planet_id).await?;
+
+        log::warn!(
+            "dispatching mission {} from {} to {} on demand",
+            dispatch.mission,
+            location,
+            dispatch.target
+        );
+        self.transport
+            .dispatch(planet_id, &location, dispatch)
+            .await
+            .map_err(metrics::webdriver_error)
+    }
+
     fn calculate_expiration() -> anyhow::Result<DateTime<Utc>> {
         // 15 minutes
         let refresh_rate = (REFRESH_RATE * MINUTE) as f32;
@@ -306,108 +954,62 @@ impl CheatBot {
         };
 
         let expired_time = Utc::now().timestamp_millis() + time_until_expiration;
-        let Some(expired_time) = Utc.timestamp_millis_opt(expired_time).single() else {return Err(anyhow!("calculate expiration time error"))};
+        let Some(expired_time) = Utc.timestamp_millis_opt(expired_time).single() else {
+            return Err(anyhow!("calculate expiration time error"));
+        };
 
         Ok(expired_time)
     }
 
-    /// login the game
-    pub async fn login(&self, account: &str, password: &str) -> anyhow::Result<()> {
-        // go to the Ogame home page
-        self.client
-            .goto("https://lobby.ogame.gameforge.com/zh_TW/")
-            .await?;
-
-        // click accept cookie button so that modal will not block content
-        let cookie_modal = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(r#"//button[@class='cookiebanner5']"#))
-            .await?;
-        cookie_modal.click().await?;
-
-        // select login tab
-        let login_tab = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(r#"//ul[@class='tabsList']/li[1]"#))
-            .await?;
-        login_tab.click().await?;
-
-        // enter account and password
-        let account_input = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(r#"//input[@type='email']"#))
-            .await?;
-        account_input.send_keys(account).await?;
-
-        let password_input = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(r#"//input[@type='password']"#))
-            .await?;
-        password_input.send_keys(password).await?;
-
-        // click login
-        let login = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(r#"//button[@type='submit']"#))
-            .await?;
-        login.click().await?;
-
-        //HACK wait so that webpage can change register button to last time played button
-        // duration depends on processing speed of computer
-        sleep(Duration::from_secs(10)).await;
-
-        // click last time played
-        let last_time_played = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(r#"//div[@id='joinGame']/button[1]"#))
-            .await?;
-        last_time_played.click().await?;
-
-        // change current window to new window(game)
-        let windows = self.client.windows().await?;
-        self.client.switch_to_window(windows[1].clone()).await?;
+    /// the planets of the planet list under the config, the planet list of the previous refresh
+    /// or the config is used when it can not be read
+    async fn discover_planets(
+        &self,
+        planet_list: &mut Option<Vec<PlanetListEntry>>,
+    ) -> Vec<PlanetId> {
+        match metrics::scrape("get_planet_list", self.get_planet_list()).await {
+            Ok(planets) => {
+                if planet_list.as_ref() != Some(&planets) {
+                    log::info!("{} planets in the planet list", planets.len());
+                    self.planets_info.warn_mismatch(&planets);
+                    *planet_list = Some(planets);
+                }
+            }
+            Err(e) => log::warn!("read planet list error: {}", e),
+        }
 
-        Ok(())
+        self.planets_info.resolve(planet_list.as_deref())
     }
 
-    pub async fn resume_play(&self) -> anyhow::Result<()> {
-        // go to the Ogame home page
-        self.client
-            .goto("https://lobby.ogame.gameforge.com/zh_TW/")
-            .await?;
-
-        // click last time played
-        let last_time_played = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(r#"//div[@id='joinGame']/button[1]"#))
-            .await?;
-        last_time_played.click().await?;
+    /// every planet of the account with its moon
+    pub async fn get_planet_list(&self) -> anyhow::Result<Vec<PlanetListEntry>> {
+        let source = self.transport.planet_list().await?;
 
-        // change current window to new window(game)
-        let windows = self.client.windows().await?;
-        self.client.switch_to_window(windows[1].clone()).await?;
-
-        Ok(())
+        parse_planet_list(&Page::parse(&source), &self.selectors.planet_list)
     }
 
     /// parse all inforamtion from empire
-    pub async fn empire_overview(&self) -> anyhow::Result<EmpireOverview> {
+    pub async fn empire_overview(&self, planets: &[PlanetId]) -> anyhow::Result<EmpireOverview> {
+        match self.scrape_mode {
+            ScrapeMode::Tabs => self.empire_overview_from_tabs(planets).await,
+            ScrapeMode::Empire => self.empire_overview_from_empire_page(planets).await,
+        }
+    }
+
+    async fn empire_overview_from_tabs(
+        &self,
+        planets: &[PlanetId],
+    ) -> anyhow::Result<EmpireOverview> {
         let mut overview = Vec::new();
 
-        for planet in self.planets_info.planets.iter() {
+        for planet in planets.iter() {
             let planet_overview = self.parse_planet(planet).await?;
             overview.push(planet_overview);
         }
 
-        let technology = self.get_technology_level().await?;
-        let maybe_fleet_events = self.get_fleet_events().await?;
+        let technology =
+            metrics::scrape("get_technology_level", self.get_technology_level()).await?;
+        let maybe_fleet_events = self.maybe_fleet_events().await;
 
         Ok(EmpireOverview {
             overview,
@@ -416,28 +1018,97 @@ impl CheatBot {
         })
     }
 
+    async fn empire_overview_from_empire_page(
+        &self,
+        configured: &[PlanetId],
+    ) -> anyhow::Result<EmpireOverview> {
+        let planets = metrics::scrape("get_empire", self.get_empire(PLANET_TYPE_PLANET)).await?;
+        let lunars = if configured.iter().any(|planet| planet.lunar_id.is_some()) {
+            metrics::scrape("get_empire", self.get_empire(PLANET_TYPE_MOON)).await?
+        } else {
+            Vec::new()
+        };
+
+        let mut overview = Vec::new();
+        for planet in configured.iter() {
+            let entry = planets
+                .iter()
+                .find(|entry| entry.id == planet.planet_id)
+                .ok_or_else(|| anyhow!("planet {} is not on the empire page", planet.planet_id))?;
+
+            let lunar = match &planet.lunar_id {
+                Some(lunar_id) => {
+                    let entry = lunars
+                        .iter()
+                        .find(|entry| entry.id == *lunar_id)
+                        .ok_or_else(|| anyhow!("moon {} is not on the empire page", lunar_id))?;
+                    Some(Lunar {
+                        id: entry.id.clone(),
+                        location: entry.location.moon(),
+                        resource: entry.resource.clone(),
+                        facility: entry.lunar_facility(),
+                        fleet: entry.fleet(),
+                    })
+                }
+                None => None,
+            };
+
+            overview.push(PlanetOverview {
+                id: entry.id.clone(),
+                location: entry.location,
+                resource: entry.resource.clone(),
+                infrastructure: entry.infrastructure(),
+                facility: entry.planet_facility(),
+                defence: entry.defence(),
+                fleet: entry.fleet(),
+                lunar,
+            });
+        }
+
+        let technology = planets
+            .first()
+            .map(EmpireEntry::technology)
+            .ok_or_else(|| anyhow!("no planet on the empire page"))?;
+
+        // the empire page is standalone, the event list is only on regular game pages
+        let Some(first) = configured.first() else {
+            return Err(anyhow!("no planet to scrape"));
+        };
+        self.transport.overview(&first.planet_id).await?;
+        let maybe_fleet_events = self.maybe_fleet_events().await;
+
+        Ok(EmpireOverview {
+            overview,
+            technology,
+            maybe_fleet_events,
+        })
+    }
+
+    /// every planet or moon of the empire page
+    pub async fn get_empire(&self, planet_type: u8) -> anyhow::Result<Vec<EmpireEntry>> {
+        parse_empire(&self.transport.empire(planet_type).await?)
+    }
+
     pub async fn parse_planet(&self, planet: &PlanetId) -> anyhow::Result<PlanetOverview> {
+        log::debug!("scraping planet {}", planet.label());
         // go to the current planet overview
-        let url = format!("https://s144-tw.ogame.gameforge.com/game/index.php?page=ingame&component=overview&cp={}", planet.planet_id);
-        self.client.goto(&url).await?;
-
-        // wait data response from server
-        sleep(Duration::from_secs(2)).await;
-        // get location
-        let location = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(r#"//span[@id='positionContentField']"#))
-            .await?
-            .text()
-            .await?;
+        let (location, resource) = metrics::scrape(
+            "get_resource",
+            self.get_location_and_resource(&planet.planet_id),
+        )
+        .await?;
 
         // get planet data
-        let resource = self.get_resource().await?;
-        let infrastructure = self.get_infrastructure_level().await?;
-        let facility = self.get_planet_facility_level().await?;
-        let defence = self.get_defense_unit_amount().await?;
-        let fleet = self.get_fleet_unit_amount().await?;
+        let infrastructure =
+            metrics::scrape("get_infrastructure_level", self.get_infrastructure_level()).await?;
+        let facility = metrics::scrape(
+            "get_planet_facility_level",
+            self.get_planet_facility_level(),
+        )
+        .await?;
+        let defence =
+            metrics::scrape("get_defense_unit_amount", self.get_defense_unit_amount()).await?;
+        let fleet = metrics::scrape("get_fleet_unit_amount", self.get_fleet_unit_amount()).await?;
 
         // get lunar data
         let lunar = self.parse_lunar(&planet.lunar_id).await?;
@@ -458,27 +1129,17 @@ impl CheatBot {
         match lunar {
             Some(id) => {
                 // go to the current lunar overview
-                let url = format!("https://s144-tw.ogame.gameforge.com/game/index.php?page=ingame&component=overview&cp={}", id);
-                self.client.goto(&url).await?;
-
-                // wait data response from server
-                sleep(Duration::from_secs(2)).await;
-                // get location
-                let location = self
-                    .client
-                    .wait()
-                    .for_element(Locator::XPath(r#"//span[@id='positionContentField']"#))
-                    .await?
-                    .text()
-                    .await?;
-
-                let resource = self.get_resource().await?;
-                let facility = self.get_lunar_facility_level().await?;
-                let fleet = self.get_fleet_unit_amount().await?;
+                let (location, resource) =
+                    metrics::scrape("get_resource", self.get_location_and_resource(id)).await?;
+                let facility =
+                    metrics::scrape("get_lunar_facility_level", self.get_lunar_facility_level())
+                        .await?;
+                let fleet =
+                    metrics::scrape("get_fleet_unit_amount", self.get_fleet_unit_amount()).await?;
 
                 Ok(Some(Lunar {
                     id: id.to_owned(),
-                    location,
+                    location: location.moon(),
                     resource,
                     facility,
                     fleet,
@@ -488,1112 +1149,104 @@ impl CheatBot {
         }
     }
 
-    /// get resources from a planet
-    pub async fn get_resource(&self) -> anyhow::Result<Resource> {
-        let metal = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(r#"//span[@id='resources_metal']"#))
-            .await?
-            .text()
-            .await?;
-
-        let crystal = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(r#"//span[@id='resources_crystal']"#))
-            .await?
-            .text()
-            .await?;
-
-        let deuterium = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(r#"//span[@id='resources_deuterium']"#))
-            .await?
-            .text()
-            .await?;
-
-        let energy = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(r#"//span[@id='resources_energy']"#))
-            .await?
-            .text()
-            .await?;
-
-        Ok(Resource {
-            metal,
-            crystal,
-            deuterium,
-            energy,
-        })
+    /// get location and resources of a planet or moon from its overview
+    pub async fn get_location_and_resource(
+        &self,
+        planet_id: &str,
+    ) -> anyhow::Result<(Coordinates, Resource)> {
+        let source = self.transport.overview(planet_id).await?;
+        let page = Page::parse(&source);
+
+        Ok((
+            parse_location(&page, &self.selectors.overview)?,
+            parse_resource(&page, &self.selectors.resource)?,
+        ))
     }
 
     /// get infrastructure level
     pub async fn get_infrastructure_level(&self) -> anyhow::Result<Infrastructure> {
-        let infrastructure_tab = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(r#"//ul[@id='menuTable']/li[2]"#))
-            .await?;
-        infrastructure_tab.click().await?;
-
-        let metal_mine = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(
-                r#"//div[@id='technologies']/ul/li[1]//span[@class='level']"#,
-            ))
-            .await?
-            .text()
-            .await?
-            .parse::<u32>()?;
-
-        let crystal_mine = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(
-                r#"//div[@id='technologies']/ul/li[2]//span[@class='level']"#,
-            ))
-            .await?
-            .text()
-            .await?
-            .parse::<u32>()?;
-
-        let deuterium_synthesizer = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(
-                r#"//div[@id='technologies']/ul/li[3]//span[@class='level']"#,
-            ))
-            .await?
-            .text()
-            .await?
-            .parse::<u32>()?;
-
-        let energy_plant = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(
-                r#"//div[@id='technologies']/ul/li[4]//span[@class='level']"#,
-            ))
-            .await?
-            .text()
-            .await?
-            .parse::<u32>()?;
-
-        let fusion_reactor = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(
-                r#"//div[@id='technologies']/ul/li[5]//span[@class='level']"#,
-            ))
-            .await?
-            .text()
-            .await?
-            .parse::<u32>()?;
-
-        let solar_satellite = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(
-                r#"//div[@id='technologies']/ul/li[6]//span[@class='amount']"#,
-            ))
-            .await?
-            .text()
-            .await?
-            .parse::<u32>()?;
-
-        let crawler = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(
-                r#"//div[@id='technologies']/ul/li[7]//span[@class='amount']"#,
-            ))
-            .await?
-            .text()
-            .await?
-            .parse::<u32>()?;
-
-        let metal_storage = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(
-                r#"//div[@id='technologies']/ul/li[8]//span[@class='level']"#,
-            ))
-            .await?
-            .text()
-            .await?
-            .parse::<u32>()?;
-
-        let crystal_storage = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(
-                r#"//div[@id='technologies']/ul/li[9]//span[@class='level']"#,
-            ))
-            .await?
-            .text()
-            .await?
-            .parse::<u32>()?;
-
-        let deuterium_tank = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(
-                r#"//div[@id='technologies']/ul/li[10]//span[@class='level']"#,
-            ))
-            .await?
-            .text()
-            .await?
-            .parse::<u32>()?;
-
-        Ok(Infrastructure {
-            metal_mine,
-            crystal_mine,
-            deuterium_synthesizer,
-            energy_plant,
-            fusion_reactor,
-            solar_satellite,
-            crawler,
-            metal_storage,
-            crystal_storage,
-            deuterium_tank,
-        })
+        let source = self.transport.tab(Tab::Supplies).await?;
+
+        parse_infrastructure(&Page::parse(&source), &self.selectors.infrastructure)
     }
 
     /// get planet facility level
     pub async fn get_planet_facility_level(&self) -> anyhow::Result<PlanetFacility> {
-        let facility_tab = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(r#"//ul[@id='menuTable']/li[4]"#))
-            .await?;
-        facility_tab.click().await?;
-
-        let robotics_factory = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(
-                r#"//div[@id='technologies']/ul/li[1]//span[@class='level']"#,
-            ))
-            .await?
-            .text()
-            .await?
-            .parse::<u32>()?;
-
-        let shipyard = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(
-                r#"//div[@id='technologies']/ul/li[2]//span[@class='level']"#,
-            ))
-            .await?
-            .text()
-            .await?
-            .parse::<u32>()?;
-
-        let research_lab = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(
-                r#"//div[@id='technologies']/ul/li[3]//span[@class='level']"#,
-            ))
-            .await?
-            .text()
-            .await?
-            .parse::<u32>()?;
-
-        let alliance_depot = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(
-                r#"//div[@id='technologies']/ul/li[4]//span[@class='level']"#,
-            ))
-            .await?
-            .text()
-            .await?
-            .parse::<u32>()?;
-
-        let missile_silo = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(
-                r#"//div[@id='technologies']/ul/li[5]//span[@class='level']"#,
-            ))
-            .await?
-            .text()
-            .await?
-            .parse::<u32>()?;
-
-        let nanite_factory = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(
-                r#"//div[@id='technologies']/ul/li[6]//span[@class='level']"#,
-            ))
-            .await?
-            .text()
-            .await?
-            .parse::<u32>()?;
-
-        let terraformer = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(
-                r#"//div[@id='technologies']/ul/li[7]//span[@class='level']"#,
-            ))
-            .await?
-            .text()
-            .await?
-            .parse::<u32>()?;
-
-        let space_dock = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(
-                r#"//div[@id='technologies']/ul/li[8]//span[@class='level']"#,
-            ))
-            .await?
-            .text()
-            .await?
-            .parse::<u32>()?;
-
-        Ok(PlanetFacility {
-            robotics_factory,
-            shipyard,
-            research_lab,
-            alliance_depot,
-            missile_silo,
-            nanite_factory,
-            terraformer,
-            space_dock,
-        })
+        let source = self.transport.tab(Tab::Facilities).await?;
+
+        parse_planet_facility(&Page::parse(&source), &self.selectors.planet_facility)
     }
 
     /// get lunar facility level
     pub async fn get_lunar_facility_level(&self) -> anyhow::Result<LunarFacility> {
-        let facility_tab = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(r#"//ul[@id='menuTable']/li[4]"#))
-            .await?;
-        facility_tab.click().await?;
-
-        let robotics_factory = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(
-                r#"//div[@id='technologies']/ul/li[1]//span[@class='level']"#,
-            ))
-            .await?
-            .text()
-            .await?
-            .parse::<u32>()?;
-
-        let shipyard = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(
-                r#"//div[@id='technologies']/ul/li[2]//span[@class='level']"#,
-            ))
-            .await?
-            .text()
-            .await?
-            .parse::<u32>()?;
-
-        let lunar_base = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(
-                r#"//div[@id='technologies']/ul/li[3]//span[@class='level']"#,
-            ))
-            .await?
-            .text()
-            .await?
-            .parse::<u32>()?;
-
-        let sensor_phalanx = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(
-                r#"//div[@id='technologies']/ul/li[4]//span[@class='level']"#,
-            ))
-            .await?
-            .text()
-            .await?
-            .parse::<u32>()?;
-
-        let jump_gate = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(
-                r#"//div[@id='technologies']/ul/li[5]//span[@class='level']"#,
-            ))
-            .await?
-            .text()
-            .await?
-            .parse::<u32>()?;
-
-        Ok(LunarFacility {
-            robotics_factory,
-            shipyard,
-            lunar_base,
-            sensor_phalanx,
-            jump_gate,
-        })
+        let source = self.transport.tab(Tab::Facilities).await?;
+
+        parse_lunar_facility(&Page::parse(&source), &self.selectors.lunar_facility)
     }
 
     /// get technology level
     pub async fn get_technology_level(&self) -> anyhow::Result<Technology> {
-        let technology_tab = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(r#"//ul[@id='menuTable']/li[6]"#))
-            .await?;
-        technology_tab.click().await?;
-
-        // basic technologies
-        let energy_technology = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(
-                r#"//div[@id='technologies_basic']/ul/li[1]//span[@class='level']"#,
-            ))
-            .await?
-            .text()
-            .await?
-            .parse::<u32>()?;
-
-        let laser_technology = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(
-                r#"//div[@id='technologies_basic']/ul/li[2]//span[@class='level']"#,
-            ))
-            .await?
-            .text()
-            .await?
-            .parse::<u32>()?;
-
-        let ion_technology = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(
-                r#"//div[@id='technologies_basic']/ul/li[3]//span[@class='level']"#,
-            ))
-            .await?
-            .text()
-            .await?
-            .parse::<u32>()?;
-
-        let hyperspace_technology = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(
-                r#"//div[@id='technologies_basic']/ul/li[4]//span[@class='level']"#,
-            ))
-            .await?
-            .text()
-            .await?
-            .parse::<u32>()?;
-
-        let plasma_technology = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(
-                r#"//div[@id='technologies_basic']/ul/li[5]//span[@class='level']"#,
-            ))
-            .await?
-            .text()
-            .await?
-            .parse::<u32>()?;
-
-        // drive technologies
-        let combustion_drive = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(
-                r#"//div[@id='technologies_drive']/ul/li[1]//span[@class='level']"#,
-            ))
-            .await?
-            .text()
-            .await?
-            .parse::<u32>()?;
-
-        let impulse_drive = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(
-                r#"//div[@id='technologies_drive']/ul/li[2]//span[@class='level']"#,
-            ))
-            .await?
-            .text()
-            .await?
-            .parse::<u32>()?;
-
-        let hyperspace_drive = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(
-                r#"//div[@id='technologies_drive']/ul/li[3]//span[@class='level']"#,
-            ))
-            .await?
-            .text()
-            .await?
-            .parse::<u32>()?;
-
-        // advanced technologies
-        let espionage_technology = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(
-                r#"//div[@id='technologies_advanced']/ul/li[1]//span[@class='level']"#,
-            ))
-            .await?
-            .text()
-            .await?;
-        // .parse::<u32>()?;
-
-        let computer_technology = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(
-                r#"//div[@id='technologies_advanced']/ul/li[2]//span[@class='level']"#,
-            ))
-            .await?
-            .text()
-            .await?
-            .parse::<u32>()?;
-
-        let astrophysics = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(
-                r#"//div[@id='technologies_advanced']/ul/li[3]//span[@class='level']"#,
-            ))
-            .await?
-            .text()
-            .await?
-            .parse::<u32>()?;
-
-        let intergalactic_research_network = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(
-                r#"//div[@id='technologies_advanced']/ul/li[4]//span[@class='level']"#,
-            ))
-            .await?
-            .text()
-            .await?
-            .parse::<u32>()?;
-
-        let graviton_technology = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(
-                r#"//div[@id='technologies_advanced']/ul/li[5]//span[@class='level']"#,
-            ))
-            .await?
-            .text()
-            .await?
-            .parse::<u32>()?;
-
-        // combat technologies
-        let armour_technology = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(
-                r#"//div[@id='technologies_combat']/ul/li[1]//span[@class='level']"#,
-            ))
-            .await?
-            .text()
-            .await?
-            .parse::<u32>()?;
-
-        let weapons_technology = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(
-                r#"//div[@id='technologies_combat']/ul/li[2]//span[@class='level']"#,
-            ))
-            .await?
-            .text()
-            .await?
-            .parse::<u32>()?;
-
-        let shielding_technology = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(
-                r#"//div[@id='technologies_combat']/ul/li[3]//span[@class='level']"#,
-            ))
-            .await?
-            .text()
-            .await?
-            .parse::<u32>()?;
-
-        Ok(Technology {
-            energy_technology,
-            laser_technology,
-            ion_technology,
-            hyperspace_technology,
-            plasma_technology,
-            combustion_drive,
-            impulse_drive,
-            hyperspace_drive,
-            espionage_technology,
-            computer_technology,
-            astrophysics,
-            intergalactic_research_network,
-            graviton_technology,
-            armour_technology,
-            weapons_technology,
-            shielding_technology,
-        })
+        let source = self.transport.tab(Tab::Research).await?;
+
+        parse_technology(&Page::parse(&source), &self.selectors.technology)
     }
 
-    /// get defence unit amount
+    /// get defense unit amount
     pub async fn get_defense_unit_amount(&self) -> anyhow::Result<Defence> {
-        let defense_tab = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(r#"//ul[@id='menuTable']/li[8]"#))
-            .await?;
-        defense_tab.click().await?;
-
-        let rocket_launcher = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(
-                r#"//div[@id='technologies']/ul/li[1]//span[@class='amount']"#,
-            ))
-            .await?
-            .text()
-            .await?
-            .replace(",", "")
-            .parse::<u32>()?;
-
-        let light_laser = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(
-                r#"//div[@id='technologies']/ul/li[2]//span[@class='amount']"#,
-            ))
-            .await?
-            .text()
-            .await?
-            .replace(",", "")
-            .parse::<u32>()?;
-
-        let heavy_laser = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(
-                r#"//div[@id='technologies']/ul/li[3]//span[@class='amount']"#,
-            ))
-            .await?
-            .text()
-            .await?
-            .replace(",", "")
-            .parse::<u32>()?;
-
-        let ion_cannon = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(
-                r#"//div[@id='technologies']/ul/li[4]//span[@class='amount']"#,
-            ))
-            .await?
-            .text()
-            .await?
-            .replace(",", "")
-            .parse::<u32>()?;
-
-        let gauss_cannon = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(
-                r#"//div[@id='technologies']/ul/li[5]//span[@class='amount']"#,
-            ))
-            .await?
-            .text()
-            .await?
-            .replace(",", "")
-            .parse::<u32>()?;
-
-        let plasma_turret = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(
-                r#"//div[@id='technologies']/ul/li[6]//span[@class='amount']"#,
-            ))
-            .await?
-            .text()
-            .await?
-            .replace(",", "")
-            .parse::<u32>()?;
-
-        let small_shield_dome = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(
-                r#"//div[@id='technologies']/ul/li[7]//span[@class='amount']"#,
-            ))
-            .await?
-            .text()
-            .await?
-            .replace(",", "")
-            .parse::<u32>()?;
-
-        let large_shield_dome = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(
-                r#"//div[@id='technologies']/ul/li[8]//span[@class='amount']"#,
-            ))
-            .await?
-            .text()
-            .await?
-            .replace(",", "")
-            .parse::<u32>()?;
-
-        let anti_ballistic_missile = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(
-                r#"//div[@id='technologies']/ul/li[9]//span[@class='amount']"#,
-            ))
-            .await?
-            .text()
-            .await?
-            .replace(",", "")
-            .parse::<u32>()?;
-
-        let interplanetary_missile = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(
-                r#"//div[@id='technologies']/ul/li[10]//span[@class='amount']"#,
-            ))
-            .await?
-            .text()
-            .await?
-            .replace(",", "")
-            .parse::<u32>()?;
-
-        Ok(Defence {
-            rocket_launcher,
-            light_laser,
-            heavy_laser,
-            ion_cannon,
-            gauss_cannon,
-            plasma_turret,
-            small_shield_dome,
-            large_shield_dome,
-            anti_ballistic_missile,
-            interplanetary_missile,
-        })
+        let source = self.transport.tab(Tab::Defence).await?;
+
+        parse_defence(&Page::parse(&source), &self.selectors.defence)
     }
 
     /// get fleet unit amount
     pub async fn get_fleet_unit_amount(&self) -> anyhow::Result<Fleet> {
-        let fleet_tab = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(r#"//ul[@id='menuTable']/li[9]"#))
-            .await?;
-        fleet_tab.click().await?;
-
-        // handle no fleet
-        if self
-            .client
-            .find_all(Locator::XPath(r#"//div[@id='fleet1']/div/div"#))
-            .await?
-            .len()
-            == 6
-        {
-            return Ok(Fleet::default());
-        };
+        let source = self.transport.tab(Tab::Fleet).await?;
 
-        let battleships = self
-            .client
-            .find_all(Locator::XPath(r#"//div[@id='battleships']/ul/li"#))
-            .await?;
-
-        let light_fighter = battleships[0]
-            .text()
-            .await?
-            .replace(",", "")
-            .parse::<u32>()?;
-
-        let heavy_fighter = battleships[1]
-            .text()
-            .await?
-            .replace(",", "")
-            .parse::<u32>()?;
-
-        let cruiser = battleships[2]
-            .text()
-            .await?
-            .replace(",", "")
-            .parse::<u32>()?;
-
-        let battleship = battleships[3]
-            .text()
-            .await?
-            .replace(",", "")
-            .parse::<u32>()?;
-
-        let battlecruiser = battleships[4]
-            .text()
-            .await?
-            .replace(",", "")
-            .parse::<u32>()?;
-
-        let bomber = battleships[5]
-            .text()
-            .await?
-            .replace(",", "")
-            .parse::<u32>()?;
-
-        let destroyer = battleships[6]
-            .text()
-            .await?
-            .replace(",", "")
-            .parse::<u32>()?;
-
-        let deathstar = battleships[7]
-            .text()
-            .await?
-            .replace(",", "")
-            .parse::<u32>()?;
-
-        let reaper = battleships[8]
-            .text()
-            .await?
-            .replace(",", "")
-            .parse::<u32>()?;
-
-        let pathfinder = battleships[9]
-            .text()
-            .await?
-            .replace(",", "")
-            .parse::<u32>()?;
-
-        // // civilships
-        let civilships = self
-            .client
-            .find_all(Locator::XPath(r#"//div[@id='civilships']/ul/li"#))
-            .await?;
-
-        let small_cargo_ship = civilships[0]
-            .text()
-            .await?
-            .replace(",", "")
-            .parse::<u32>()?;
-
-        let large_cargo_ship = civilships[1]
-            .text()
-            .await?
-            .replace(",", "")
-            .parse::<u32>()?;
-
-        let colony_ship = civilships[2]
-            .text()
-            .await?
-            .replace(",", "")
-            .parse::<u32>()?;
-
-        let recycler = civilships[3]
-            .text()
-            .await?
-            .replace(",", "")
-            .parse::<u32>()?;
-
-        let espionage_probe = civilships[4]
-            .text()
-            .await?
-            .replace(",", "")
-            .parse::<u32>()?;
-
-        Ok(Fleet {
-            light_fighter,
-            heavy_fighter,
-            cruiser,
-            battleship,
-            battlecruiser,
-            bomber,
-            destroyer,
-            deathstar,
-            reaper,
-            pathfinder,
-            small_cargo_ship,
-            large_cargo_ship,
-            colony_ship,
-            recycler,
-            espionage_probe,
-        })
+        parse_fleet(&Page::parse(&source), &self.selectors.fleet)
     }
 
-    pub async fn get_fleet_events(&self) -> anyhow::Result<Option<Vec<FleetEvent>>> {
-        // wait for page loading
-        sleep(Duration::from_secs(1)).await;
-        // trigger drop for fetching data
-        let event_drop_down = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(r#"//a[@id='js_eventDetailsClosed']"#))
-            .await?;
-
-        if let Err(e) = event_drop_down.click().await {
-            log::info!("{}, might not have any events", e);
-            return Ok(None);
-        }
-
-        let event_content = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(r#"//table[@id='eventContent']/tbody"#))
-            .await?;
-
-        let events = event_content.find_all(Locator::XPath(r#"tr"#)).await?;
-
-        let mut fleet_events = Vec::new();
-
-        for event in events {
-            let Some(mission) = event
-                .find(Locator::XPath(r#"td[@class='missionFleet']/img"#))
-                .await?
-                .attr("title")
-                .await? else { return Err(anyhow::anyhow!("parse mission type error"))};
-
-            let arrival_time = event
-                .find(Locator::XPath(r#"td[@class='arrivalTime']"#))
-                .await?
-                .text()
-                .await?;
-
-            let coords_origin = event
-                .find(Locator::XPath(r#"td[@class='coordsOrigin']"#))
-                .await?
-                .text()
-                .await?;
-
-            let dest_coords = event
-                .find(Locator::XPath(r#"td[@class='destCoords']"#))
-                .await?
-                .text()
-                .await?;
-
-            fleet_events.push(FleetEvent {
-                mission_type: MissionType::try_from(mission)?,
-                arrival_time,
-                coords_origin,
-                dest_coords,
-            });
+    /// every fleet event of the event list, none while no fleet is under way
+    pub async fn get_fleet_events(&self) -> anyhow::Result<Vec<FleetEvent>> {
+        match self.transport.event_list().await? {
+            Some(source) => parse_fleet_events(
+                &Page::parse(&source),
+                &self.selectors.fleet_event,
+                Utc::now().with_timezone(&self.timezone),
+            ),
+            None => Ok(Vec::new()),
         }
-
-        // close drop down
-        let event_drop_down = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(r#"//a[@id='js_eventDetailsOpen']"#))
-            .await?;
-        event_drop_down.click().await?;
-
-        Ok(Some(fleet_events))
     }
 
-    pub async fn fleet_saving(&self, id: &str) -> anyhow::Result<()> {
-        let url = format!("https://s144-tw.ogame.gameforge.com/game/index.php?page=ingame&component=fleetdispatch&cp={}",id);
-        self.client.goto(&url).await?;
-
-        // select all fleets
-        self.client
-            .wait()
-            .for_element(Locator::XPath(r#"//span[@class='send_all']/a"#))
-            .await?
-            .click()
-            .await?;
-
-        // next step
-        self.client
-            .wait()
-            .for_element(Locator::XPath(r#"//a[@id='continueToFleet2']/span"#))
-            .await?
-            .click()
-            .await?;
-
-        sleep(Duration::from_secs(3)).await;
-
-        // enter coords
-        self.client
-            .wait()
-            .for_element(Locator::XPath(
-                r#"//div[@class='coords']//input[@id='position']"#,
-            ))
-            .await?
-            .send_keys("16")
-            .await?;
-
-        // select expedition
-        self.client
-            .wait()
-            .for_element(Locator::XPath(
-                r#"//ul[@id='missions']//li[@id='button15']/a"#,
-            ))
-            .await?
-            .click()
-            .await?;
-
-        // select 10% speed
-        self.client
-            .wait()
-            .for_element(Locator::XPath(r#"//div[@class='steps']/div[1]"#))
-            .await?
-            .click()
-            .await?;
-
-        // load all resources
-        self.client
-            .wait()
-            .for_element(Locator::XPath(r#"//div[@id='loadAllResources']/a"#))
-            .await?
-            .click()
-            .await?;
-
-        // dispatch fleets
-        self.client
-            .wait()
-            .for_element(Locator::XPath(
-                r#"//div[@id='naviActions']//a[@id='sendFleet']"#,
-            ))
-            .await?
-            .click()
-            .await?;
-
-        Ok(())
+    /// the fleet events of a refresh, none if the event list can not be read, the refresh goes on
+    /// without them
+    async fn maybe_fleet_events(&self) -> Option<Vec<FleetEvent>> {
+        match metrics::scrape("get_fleet_events", self.get_fleet_events()).await {
+            Ok(events) => Some(events),
+            Err(e) => {
+                log::error!(
+                    "read fleet events error, the events stay as they were: {}",
+                    e
+                );
+                None
+            }
+        }
     }
-}
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-pub struct FleetEvent {
-    pub mission_type: MissionType,
-    pub arrival_time: String,
-    pub coords_origin: String,
-    pub dest_coords: String,
-}
+    /// every fleet of the account under way
+    pub async fn get_fleet_movements(&self) -> anyhow::Result<Vec<FleetMovement>> {
+        let source = self.transport.movement().await?;
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
-pub enum MissionType {
-    // Dispatch
-    // Self
-    Expedition,
-    Colonization,
-    Harvesting,
-    Transport,
-    Deployment,
-    Espionage,
-    ACSDefend,
-    Attacking,
-    ACSAttack,
-    Destroy,
-    SearchingForLifeforms,
-    // Return
-    ExpeditionReturn,
-    ColonizationReturn,
-    HarvestingReturn,
-    TransportReturn,
-    DeploymentReturn,
-    EspionageReturn,
-    ACSDefendReturn,
-    AttackingReturn,
-    ACSAttackReturn,
-    DestroyReturn,
-    SearchingForLifeformsReturn,
-    // Ally
-    FriendlyTransport,
-    FriendlyACSDefend,
-    // Enemy
-    EnemyEspionage,
-    EnemyAttacking,
-}
-
-impl TryFrom<String> for MissionType {
-    type Error = anyhow::Error;
-
-    fn try_from(str: String) -> Result<Self, Self::Error> {
-        match str.as_str() {
-            // Self Dispatch
-            EXPEDITION => Ok(MissionType::Expedition),
-            COLONIZATION => Ok(MissionType::Colonization),
-            HARVESTING => Ok(MissionType::Harvesting),
-            TRANSPORT => Ok(MissionType::Transport),
-            DEPLOYMENT => Ok(MissionType::Deployment),
-            ESPIONAGE => Ok(MissionType::Espionage),
-            ACS_DEFEND => Ok(MissionType::ACSDefend),
-            ATTACKING => Ok(MissionType::Attacking),
-            ACS_ATTACK => Ok(MissionType::ACSAttack),
-            DESTROY => Ok(MissionType::Destroy),
-            SEARCHING_FOR_LIFEFORMS => Ok(MissionType::SearchingForLifeforms),
-            // Self Return
-            EXPEDITION_RETURN => Ok(MissionType::ExpeditionReturn),
-            COLONIZATION_RETURN => Ok(MissionType::ColonizationReturn),
-            HARVESTING_RETURN => Ok(MissionType::HarvestingReturn),
-            TRANSPORT_RETURN => Ok(MissionType::TransportReturn),
-            DEPLOYMENT_RETURN => Ok(MissionType::DeploymentReturn),
-            ESPIONAGE_RETURN => Ok(MissionType::EspionageReturn),
-            ACS_DEFEND_RETURN => Ok(MissionType::ACSDefendReturn),
-            ATTACKING_RETURN => Ok(MissionType::AttackingReturn),
-            ACS_ATTACK_RETURN => Ok(MissionType::ACSAttackReturn),
-            DESTROY_RETURN => Ok(MissionType::DestroyReturn),
-            SEARCHING_FOR_LIFEFORMS_RETURN => Ok(MissionType::SearchingForLifeformsReturn),
-            // Ally
-            FRIENDLY_TRANSPORT => Ok(MissionType::FriendlyTransport),
-            FRIENDLY_ACS_DEFEND => Ok(MissionType::FriendlyACSDefend),
-            // Enemy
-            ENEMY_ESPIONAGE => Ok(MissionType::EnemyEspionage),
-            ENEMY_ATTACKING => Ok(MissionType::EnemyAttacking),
-
-            _ => Err(anyhow::anyhow!("unknown fleet event")),
-        }
+        parse_movement(&Page::parse(&source), &self.selectors.movement)
     }
 }
 
-/// action
-pub const EXPEDITION: &str = "己方艦隊 | 遠征探險";
-pub const EXPEDITION_RETURN: &str = "己方艦隊 | 遠征探險 (返)";
-pub const COLONIZATION: &str = "己方艦隊 | 殖民";
-pub const COLONIZATION_RETURN: &str = "己方艦隊 | 殖民 (返)";
-pub const HARVESTING: &str = "己方艦隊 | 採集回收";
-pub const HARVESTING_RETURN: &str = "己方艦隊 | 採集回收 (返)";
-pub const TRANSPORT: &str = "己方艦隊 | 運輸";
-pub const TRANSPORT_RETURN: &str = "己方艦隊 | 運輸 (返)";
-pub const DEPLOYMENT: &str = "己方艦隊 | 部署";
-pub const DEPLOYMENT_RETURN: &str = "己方艦隊 | 部署 (返)";
-pub const ESPIONAGE: &str = "己方艦隊 | 間諜偵察";
-pub const ESPIONAGE_RETURN: &str = "己方艦隊 | 間諜偵察 (返)";
-pub const ACS_DEFEND: &str = "己方艦隊 | ACS聯合防禦";
-pub const ACS_DEFEND_RETURN: &str = "己方艦隊 | ACS聯合防禦 (返)";
-pub const ATTACKING: &str = "己方艦隊 | 攻擊";
-pub const ATTACKING_RETURN: &str = "己方艦隊 | 攻擊 (返)";
-pub const ACS_ATTACK: &str = "己方艦隊 | ACS聯合攻擊";
-pub const ACS_ATTACK_RETURN: &str = "己方艦隊 | ACS聯合攻擊 (返)";
-pub const DESTROY: &str = "己方艦隊 | 摧毀月球";
-pub const DESTROY_RETURN: &str = "己方艦隊 | 摧毀月球 (返)";
-pub const SEARCHING_FOR_LIFEFORMS: &str = "友方艦隊 | 搜索生命形式";
-pub const SEARCHING_FOR_LIFEFORMS_RETURN: &str = "友方艦隊 | 搜索生命形式 (返)";
-
-pub const FRIENDLY_TRANSPORT: &str = "友方艦隊 | 運輸";
-pub const FRIENDLY_ACS_DEFEND: &str = "友方艦隊 | ACS聯合防禦";
-
-pub const ENEMY_ESPIONAGE: &str = "敵方艦隊 | 間諜偵察";
-pub const ENEMY_ATTACKING: &str = "敵方艦隊 | 攻擊";
+// `planetType` of the empire page
+const PLANET_TYPE_PLANET: u8 = 0;
+const PLANET_TYPE_MOON: u8 = 1;
 
 // refresh rate
 pub const SECOND: u32 = 1000;
 pub const MINUTE: u32 = SECOND * 60;
+#[allow(dead_code)]
 pub const HOUR: u32 = MINUTE * 60;
 pub const REFRESH_RATE: u32 = 15;
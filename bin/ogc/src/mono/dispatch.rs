@@ -0,0 +1,190 @@
+use super::fleet_save::{FleetSaveStrategy, Load};
+
+use std::{collections::BTreeMap, fmt};
+
+use ogc_core::{
+    coordinates::Coordinates,
+    model::{Fleet, Resource},
+    parser::{
+        fleet_dispatch::{parse_fleet_dispatch, FleetDispatch},
+        html::Page,
+        planet::{parse_fleet, parse_resource},
+    },
+    selector::Selectors,
+};
+
+use anyhow::anyhow;
+
+/// a fleet sent on command, e.g. over gRPC
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Dispatch {
+    pub target: Coordinates,
+    /// e.g. `ogc_core::model::mission::DEPLOYMENT`
+    pub mission: u32,
+    /// tenths of the full speed, 1 to 10
+    pub speed: u8,
+    /// ship amounts by id, every ship of the planet or moon if empty
+    pub ships: BTreeMap<u32, u32>,
+    pub cargo: Load,
+}
+
+/// what the fleet dispatch form is filled with, every amount is checked against the page
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FleetOrder {
+    /// ship amounts by id, none is zero
+    pub ships: Vec<(u32, u32)>,
+    pub destination: Coordinates,
+    pub mission: u32,
+    pub speed: u8,
+    pub load: Load,
+}
+
+impl fmt::Display for FleetOrder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "mission {} to {} at {}%",
+            self.mission,
+            self.destination,
+            u32::from(self.speed) * 10
+        )
+    }
+}
+
+/// the fleet dispatch page of a planet or moon
+#[derive(Clone, Debug)]
+pub struct DispatchPage {
+    pub fleet: Fleet,
+    pub resource: Resource,
+    pub dispatch: FleetDispatch,
+}
+
+impl DispatchPage {
+    pub fn parse(source: &str, selectors: &Selectors) -> anyhow::Result<Self> {
+        let (fleet, resource) = {
+            let page = Page::parse(source);
+            (
+                parse_fleet(&page, &selectors.fleet)?,
+                parse_resource(&page, &selectors.resource)?,
+            )
+        };
+
+        Ok(DispatchPage {
+            fleet,
+            resource,
+            dispatch: parse_fleet_dispatch(source)?,
+        })
+    }
+
+    fn check_slots(&self) -> anyhow::Result<()> {
+        if self.dispatch.slots.is_some_and(|slots| slots.is_full()) {
+            return Err(anyhow!("no free fleet slot"));
+        }
+
+        Ok(())
+    }
+
+    /// every ship of `location` away by `strategy`
+    pub fn fleet_save_order(
+        &self,
+        location: &Coordinates,
+        strategy: &FleetSaveStrategy,
+    ) -> anyhow::Result<FleetOrder> {
+        if self.fleet.is_zero() {
+            return Err(anyhow!("no ship on {}", location));
+        }
+        self.check_slots()?;
+        let ships = self
+            .fleet
+            .units()
+            .into_iter()
+            .filter(|(_, amount)| *amount > 0)
+            .collect::<Vec<_>>();
+        let destination = strategy.destination(location);
+        let consumption =
+            self.dispatch
+                .consumption(&ships, location.distance(&destination), strategy.speed);
+        strategy.check(location, &self.fleet, &self.resource, consumption)?;
+        let load = strategy.load(&self.resource, self.dispatch.capacity(&ships), consumption);
+
+        Ok(FleetOrder {
+            ships,
+            destination,
+            mission: strategy.mission_id(),
+            speed: strategy.speed,
+            load,
+        })
+    }
+
+    /// `dispatch` from `location`, refused if the ships or the cargo are not there
+    pub fn dispatch_order(
+        &self,
+        location: &Coordinates,
+        dispatch: &Dispatch,
+    ) -> anyhow::Result<FleetOrder> {
+        if !(1..=10).contains(&dispatch.speed) {
+            return Err(anyhow!(
+                "dispatch speed {} is not between 1 and 10",
+                dispatch.speed
+            ));
+        }
+        if dispatch.target == *location {
+            return Err(anyhow!("{} can not dispatch to itself", location));
+        }
+        self.check_slots()?;
+
+        let units = self.fleet.units();
+        let ships = if dispatch.ships.is_empty() {
+            units
+                .into_iter()
+                .filter(|(_, amount)| *amount > 0)
+                .collect::<Vec<_>>()
+        } else {
+            dispatch
+                .ships
+                .iter()
+                .filter(|(_, amount)| **amount > 0)
+                .map(|(id, amount)| {
+                    let available = units
+                        .iter()
+                        .find(|(unit, _)| unit == id)
+                        .map(|(_, available)| *available)
+                        .ok_or_else(|| anyhow!("unknown ship {}", id))?;
+                    if *amount > available {
+                        return Err(anyhow!("only {} of ship {} on {}", available, id, location));
+                    }
+                    Ok((*id, *amount))
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?
+        };
+        if ships.is_empty() {
+            return Err(anyhow!("no ship on {}", location));
+        }
+
+        let cargo = dispatch.cargo;
+        for (name, amount, available) in [
+            ("metal", cargo.metal, self.resource.metal.0),
+            ("crystal", cargo.crystal, self.resource.crystal.0),
+            ("deuterium", cargo.deuterium, self.resource.deuterium.0),
+        ] {
+            if amount < 0 {
+                return Err(anyhow!("negative {} cargo", name));
+            }
+            if amount > available {
+                return Err(anyhow!("only {} {} on {}", available, name, location));
+            }
+        }
+        let capacity = self.dispatch.capacity(&ships);
+        if cargo.metal + cargo.crystal + cargo.deuterium > capacity {
+            return Err(anyhow!("cargo exceeds the capacity of {}", capacity));
+        }
+
+        Ok(FleetOrder {
+            ships,
+            destination: dispatch.target,
+            mission: dispatch.mission,
+            speed: dispatch.speed,
+            load: cargo,
+        })
+    }
+}
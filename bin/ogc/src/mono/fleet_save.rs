@@ -0,0 +1,604 @@
+use std::fmt;
+
+use ogc_core::{
+    coordinates::{BodyType, Coordinates},
+    model::{mission, Fleet, Resource},
+};
+
+use anyhow::anyhow;
+use chrono::Duration;
+use serde::{Deserialize, Serialize};
+
+/// position of an expedition in every system
+const EXPEDITION_POSITION: u32 = 16;
+
+/// how a fleet under attack is saved
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FleetSaveConfig {
+    /// the fleet leaves this many seconds before the attack lands
+    #[serde(default = "default_margin_seconds")]
+    pub margin_seconds: i64,
+    /// tried in order for every planet and moon without its own, until the game takes one
+    #[serde(default = "default_strategies")]
+    pub strategies: Vec<FleetSaveStrategy>,
+    /// strategies of single planets or moons
+    #[serde(default)]
+    pub planets: Vec<PlanetStrategies>,
+    #[serde(default)]
+    pub recall: RecallConfig,
+}
+
+impl Default for FleetSaveConfig {
+    fn default() -> Self {
+        FleetSaveConfig {
+            margin_seconds: default_margin_seconds(),
+            strategies: default_strategies(),
+            planets: Vec::new(),
+            recall: RecallConfig::default(),
+        }
+    }
+}
+
+impl FleetSaveConfig {
+    pub fn margin(&self) -> Duration {
+        Duration::seconds(self.margin_seconds)
+    }
+
+    /// the strategies of a planet or moon, in the order they are tried
+    pub fn strategies_of(&self, planet_id: &str) -> &[FleetSaveStrategy] {
+        self.planets
+            .iter()
+            .find(|planet| planet.planet_id == planet_id)
+            .map_or(&self.strategies, |planet| &planet.strategies)
+    }
+
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.recall.delay_seconds < 0 {
+            return Err(anyhow!(
+                "recall delay {} is negative",
+                self.recall.delay_seconds
+            ));
+        }
+        for strategy in self
+            .strategies
+            .iter()
+            .chain(self.planets.iter().flat_map(|planet| &planet.strategies))
+        {
+            strategy.validate()?;
+        }
+        if let Some(planet) = self
+            .planets
+            .iter()
+            .find(|planet| planet.strategies.is_empty())
+        {
+            return Err(anyhow!(
+                "planet {} has no fleet save strategy",
+                planet.planet_id
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// when a saved fleet is called back
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RecallConfig {
+    /// saved fleets are left under way if disabled
+    #[serde(default = "default_recall_enabled")]
+    pub enabled: bool,
+    /// the fleet is recalled this many seconds after the attack is over
+    #[serde(default = "default_recall_delay_seconds")]
+    pub delay_seconds: i64,
+}
+
+impl Default for RecallConfig {
+    fn default() -> Self {
+        RecallConfig {
+            enabled: default_recall_enabled(),
+            delay_seconds: default_recall_delay_seconds(),
+        }
+    }
+}
+
+impl RecallConfig {
+    pub fn delay(&self) -> Duration {
+        Duration::seconds(self.delay_seconds)
+    }
+}
+
+fn default_recall_enabled() -> bool {
+    true
+}
+
+/// a second wave often follows the first one closely
+fn default_recall_delay_seconds() -> i64 {
+    60
+}
+
+/// a refresh of the tabs of every planet takes a few minutes
+fn default_margin_seconds() -> i64 {
+    300
+}
+
+fn default_strategies() -> Vec<FleetSaveStrategy> {
+    vec![FleetSaveStrategy {
+        mission: SaveMission::Expedition,
+        speed: default_speed(),
+        load: default_load(),
+    }]
+}
+
+/// the strategies of a planet or moon, by its id
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanetStrategies {
+    pub planet_id: String,
+    pub strategies: Vec<FleetSaveStrategy>,
+}
+
+/// where and how every ship of a planet or moon is sent away
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FleetSaveStrategy {
+    #[serde(flatten)]
+    pub mission: SaveMission,
+    /// tenths of the full speed, 1 to 10
+    #[serde(default = "default_speed")]
+    pub speed: u8,
+    /// loaded in this order while cargo capacity is left, the deuterium of the flight stays
+    #[serde(default = "default_load")]
+    pub load: Vec<Cargo>,
+}
+
+/// the slowest flight keeps the fleet away the longest
+fn default_speed() -> u8 {
+    1
+}
+
+// crystal is loaded before metal, it is worth more
+fn default_load() -> Vec<Cargo> {
+    vec![Cargo::Crystal, Cargo::Metal]
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(tag = "mission", rename_all = "camelCase")]
+pub enum SaveMission {
+    /// station at an own planet or moon, e.g. `[1:234:8] moon`
+    Deploy { target: Coordinates },
+    /// collect the debris field at a position, needs recyclers or pathfinders
+    Harvest { target: Coordinates },
+    /// explore position 16 of the same system
+    Expedition,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum Cargo {
+    Metal,
+    Crystal,
+    Deuterium,
+}
+
+/// resources loaded onto a fleet
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Load {
+    pub metal: i64,
+    pub crystal: i64,
+    pub deuterium: i64,
+}
+
+impl FleetSaveStrategy {
+    fn validate(&self) -> anyhow::Result<()> {
+        if !(1..=10).contains(&self.speed) {
+            return Err(anyhow!(
+                "fleet save speed {} is not between 1 and 10",
+                self.speed
+            ));
+        }
+        if let SaveMission::Deploy { target } = self.mission {
+            if target.body == BodyType::Debris {
+                return Err(anyhow!("can not deploy to the debris field {}", target));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// `mission` of the fleet dispatch
+    pub fn mission_id(&self) -> u32 {
+        match self.mission {
+            SaveMission::Deploy { .. } => mission::DEPLOYMENT,
+            SaveMission::Harvest { .. } => mission::HARVESTING,
+            SaveMission::Expedition => mission::EXPEDITION,
+        }
+    }
+
+    /// where a fleet of `location` flies to
+    pub fn destination(&self, location: &Coordinates) -> Coordinates {
+        match self.mission {
+            SaveMission::Deploy { target } => target,
+            SaveMission::Harvest { target } => target.with_body(BodyType::Debris),
+            SaveMission::Expedition => Coordinates::new(
+                location.galaxy,
+                location.system,
+                EXPEDITION_POSITION,
+                BodyType::Planet,
+            ),
+        }
+    }
+
+    /// why `fleet` of `location` can not fly this strategy for `consumption` deuterium, the game
+    /// may still refuse it, e.g. when the target is occupied
+    pub fn check(
+        &self,
+        location: &Coordinates,
+        fleet: &Fleet,
+        resource: &Resource,
+        consumption: i64,
+    ) -> anyhow::Result<()> {
+        if resource.deuterium.0 < consumption {
+            return Err(anyhow!(
+                "{} deuterium on {} can not pay the flight of {}",
+                resource.deuterium,
+                location,
+                consumption
+            ));
+        }
+
+        match self.mission {
+            SaveMission::Deploy { target } if target == *location => {
+                Err(anyhow!("{} can not deploy to itself", location))
+            }
+            SaveMission::Harvest { .. } if fleet.recycler == 0 && fleet.pathfinder == 0 => {
+                Err(anyhow!("no recycler or pathfinder on {}", location))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// the resources of `resource` loaded by the rule, up to `capacity`, the `consumption` of the
+    /// flight is left in the deuterium
+    pub fn load(&self, resource: &Resource, mut capacity: i64, consumption: i64) -> Load {
+        let mut load = Load::default();
+        for cargo in self.load.iter() {
+            let (available, loaded) = match cargo {
+                Cargo::Metal => (resource.metal.0, &mut load.metal),
+                Cargo::Crystal => (resource.crystal.0, &mut load.crystal),
+                Cargo::Deuterium => (
+                    resource.deuterium.0.saturating_sub(consumption),
+                    &mut load.deuterium,
+                ),
+            };
+            *loaded = available.clamp(0, capacity.max(0));
+            capacity -= *loaded;
+        }
+
+        load
+    }
+}
+
+impl fmt::Display for FleetSaveStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.mission {
+            SaveMission::Deploy { target } => write!(f, "deploy to {}", target)?,
+            SaveMission::Harvest { target } => write!(f, "harvest {}", target.planet())?,
+            SaveMission::Expedition => write!(f, "expedition")?,
+        }
+        write!(f, " at {}%", u32::from(self.speed) * 10)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ogc_core::amount::Amount;
+
+    const PLANET: Coordinates = Coordinates {
+        galaxy: 1,
+        system: 234,
+        position: 5,
+        body: BodyType::Planet,
+    };
+    const MOON: Coordinates = Coordinates {
+        body: BodyType::Moon,
+        ..PLANET
+    };
+    const ELSEWHERE: Coordinates = Coordinates {
+        galaxy: 1,
+        system: 234,
+        position: 8,
+        body: BodyType::Planet,
+    };
+
+    fn strategy(mission: SaveMission) -> FleetSaveStrategy {
+        FleetSaveStrategy {
+            mission,
+            speed: default_speed(),
+            load: default_load(),
+        }
+    }
+
+    fn resource(metal: i64, crystal: i64, deuterium: i64) -> Resource {
+        Resource {
+            metal: Amount(metal),
+            crystal: Amount(crystal),
+            deuterium: Amount(deuterium),
+            energy: Amount(0),
+        }
+    }
+
+    #[test]
+    fn destination() {
+        let cases = [
+            (
+                SaveMission::Deploy {
+                    target: ELSEWHERE.moon(),
+                },
+                PLANET,
+                ELSEWHERE.moon(),
+                mission::DEPLOYMENT,
+            ),
+            (
+                SaveMission::Deploy { target: PLANET },
+                MOON,
+                PLANET,
+                mission::DEPLOYMENT,
+            ),
+            (
+                SaveMission::Harvest { target: ELSEWHERE },
+                PLANET,
+                ELSEWHERE.with_body(BodyType::Debris),
+                mission::HARVESTING,
+            ),
+            (
+                SaveMission::Harvest {
+                    target: ELSEWHERE.moon(),
+                },
+                MOON,
+                ELSEWHERE.with_body(BodyType::Debris),
+                mission::HARVESTING,
+            ),
+            (
+                SaveMission::Expedition,
+                PLANET,
+                Coordinates::new(1, 234, 16, BodyType::Planet),
+                mission::EXPEDITION,
+            ),
+            (
+                SaveMission::Expedition,
+                MOON,
+                Coordinates::new(1, 234, 16, BodyType::Planet),
+                mission::EXPEDITION,
+            ),
+        ];
+        for (mission, location, expected, mission_id) in cases {
+            let strategy = strategy(mission);
+            assert_eq!(strategy.destination(&location), expected, "{}", strategy);
+            assert_eq!(strategy.mission_id(), mission_id, "{}", strategy);
+        }
+    }
+
+    #[test]
+    fn check() {
+        let fighters = Fleet {
+            light_fighter: 10,
+            ..Fleet::default()
+        };
+        let recyclers = Fleet {
+            recycler: 2,
+            ..fighters.clone()
+        };
+        let pathfinders = Fleet {
+            pathfinder: 1,
+            ..fighters.clone()
+        };
+        let fueled = resource(100, 100, 100);
+        let dry = resource(100, 100, 0);
+        let debris = SaveMission::Harvest { target: ELSEWHERE };
+        // every flight costs 1 deuterium at least
+        let cases = [
+            (SaveMission::Expedition, PLANET, &fighters, &fueled, 1, true),
+            (SaveMission::Expedition, MOON, &fighters, &fueled, 1, true),
+            (SaveMission::Expedition, PLANET, &fighters, &dry, 1, false),
+            (
+                SaveMission::Expedition,
+                PLANET,
+                &fighters,
+                &fueled,
+                100,
+                true,
+            ),
+            (
+                SaveMission::Expedition,
+                PLANET,
+                &fighters,
+                &fueled,
+                101,
+                false,
+            ),
+            (
+                SaveMission::Deploy { target: PLANET },
+                PLANET,
+                &fighters,
+                &fueled,
+                1,
+                false,
+            ),
+            (
+                SaveMission::Deploy { target: MOON },
+                MOON,
+                &fighters,
+                &fueled,
+                1,
+                false,
+            ),
+            // a moon without a moon target deploys to its planet
+            (
+                SaveMission::Deploy { target: PLANET },
+                MOON,
+                &fighters,
+                &fueled,
+                1,
+                true,
+            ),
+            (
+                SaveMission::Deploy { target: MOON },
+                PLANET,
+                &fighters,
+                &fueled,
+                1,
+                true,
+            ),
+            (
+                SaveMission::Deploy { target: MOON },
+                PLANET,
+                &fighters,
+                &dry,
+                1,
+                false,
+            ),
+            (debris, PLANET, &fighters, &fueled, 1, false),
+            (debris, PLANET, &recyclers, &fueled, 1, true),
+            (debris, MOON, &pathfinders, &fueled, 1, true),
+            (debris, PLANET, &recyclers, &dry, 1, false),
+        ];
+        for (mission, location, fleet, resource, consumption, ok) in cases {
+            let strategy = strategy(mission);
+            assert_eq!(
+                strategy
+                    .check(&location, fleet, resource, consumption)
+                    .is_ok(),
+                ok,
+                "{} from {}",
+                strategy,
+                location
+            );
+        }
+    }
+
+    #[test]
+    fn validate() {
+        let cases = [
+            (SaveMission::Expedition, 0, false),
+            (SaveMission::Expedition, 1, true),
+            (SaveMission::Expedition, 10, true),
+            (SaveMission::Expedition, 11, false),
+            (SaveMission::Deploy { target: MOON }, 5, true),
+            (
+                SaveMission::Deploy {
+                    target: ELSEWHERE.with_body(BodyType::Debris),
+                },
+                5,
+                false,
+            ),
+            (
+                SaveMission::Harvest {
+                    target: ELSEWHERE.with_body(BodyType::Debris),
+                },
+                5,
+                true,
+            ),
+        ];
+        for (mission, speed, ok) in cases {
+            let strategy = FleetSaveStrategy {
+                speed,
+                ..strategy(mission)
+            };
+            assert_eq!(strategy.validate().is_ok(), ok, "{}", strategy);
+            let config = FleetSaveConfig {
+                strategies: vec![strategy.clone()],
+                ..FleetSaveConfig::default()
+            };
+            assert_eq!(config.validate().is_ok(), ok, "{}", strategy);
+        }
+    }
+
+    #[test]
+    fn validate_config() {
+        assert!(FleetSaveConfig::default().validate().is_ok());
+
+        let negative_delay = FleetSaveConfig {
+            recall: RecallConfig {
+                enabled: true,
+                delay_seconds: -1,
+            },
+            ..FleetSaveConfig::default()
+        };
+        assert!(negative_delay.validate().is_err());
+
+        let without_strategy = FleetSaveConfig {
+            planets: vec![PlanetStrategies {
+                planet_id: "1001".to_owned(),
+                strategies: Vec::new(),
+            }],
+            ..FleetSaveConfig::default()
+        };
+        assert!(without_strategy.validate().is_err());
+
+        let invalid_of_planet = FleetSaveConfig {
+            planets: vec![PlanetStrategies {
+                planet_id: "1001".to_owned(),
+                strategies: vec![FleetSaveStrategy {
+                    speed: 0,
+                    ..strategy(SaveMission::Expedition)
+                }],
+            }],
+            ..FleetSaveConfig::default()
+        };
+        assert!(invalid_of_planet.validate().is_err());
+    }
+
+    #[test]
+    fn load() {
+        use Cargo::*;
+
+        let load = |metal, crystal, deuterium| Load {
+            metal,
+            crystal,
+            deuterium,
+        };
+        let cases = [
+            (vec![Crystal, Metal], 1_000_000, 0, load(300, 50, 0)),
+            (vec![Crystal, Metal], 100, 0, load(50, 50, 0)),
+            (vec![Crystal, Metal], 30, 0, load(0, 30, 0)),
+            (vec![Metal, Crystal, Deuterium], 320, 0, load(300, 20, 0)),
+            (vec![Deuterium], 1_000, 0, load(0, 0, 20)),
+            (vec![Deuterium, Metal], 25, 0, load(5, 0, 20)),
+            (vec![], 1_000, 0, load(0, 0, 0)),
+            (vec![Crystal, Metal], 0, 0, load(0, 0, 0)),
+            (vec![Crystal, Metal], -10, 0, load(0, 0, 0)),
+            // the deuterium of the flight is not loaded
+            (vec![Deuterium], 1_000, 8, load(0, 0, 12)),
+            (vec![Deuterium, Metal], 25, 8, load(13, 0, 12)),
+            (vec![Deuterium], 1_000, 20, load(0, 0, 0)),
+            (vec![Deuterium, Metal], 25, 30, load(25, 0, 0)),
+            (vec![Crystal, Metal], 1_000_000, 20, load(300, 50, 0)),
+        ];
+        let available = resource(300, 50, 20);
+        for (rule, capacity, consumption, expected) in cases {
+            let strategy = FleetSaveStrategy {
+                load: rule.clone(),
+                ..strategy(SaveMission::Expedition)
+            };
+            assert_eq!(
+                strategy.load(&available, capacity, consumption),
+                expected,
+                "{:?} into {} burning {}",
+                rule,
+                capacity,
+                consumption
+            );
+        }
+
+        // resources below zero are not loaded
+        let strategy = strategy(SaveMission::Expedition);
+        assert_eq!(
+            strategy.load(&resource(100, -5, 20), 1_000, 1),
+            load(100, 0, 0)
+        );
+    }
+}
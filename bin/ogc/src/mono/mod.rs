@@ -1,31 +1,39 @@
-mod bot;
+pub mod bot;
+pub mod dispatch;
+pub mod fleet_save;
+#[cfg(test)]
+pub(crate) mod mock;
+pub mod transport;
 
 use crate::{
+    api::{
+        auth::{JwtKeys, MetricsToken},
+        AccountId, ApiState,
+    },
     cli::SharedParams,
-    mono::bot::{CheatBot, Config, MissionType},
+    mono::bot::{BotHandles, BotSettings, BotState, BotStatus, CheatBot, Config, SharedOverview},
 };
 
-use std::{io::Write, path::PathBuf, sync::Arc};
+use std::{io::Write, net::SocketAddr, path::PathBuf, sync::Arc};
 
-use ogc_core::sqlx_postgres::connect_and_migrate;
-
-use actix_cors::Cors;
-use actix_web::{
-    http::header,
-    middleware,
-    rt::System,
-    web::{self, Data},
-    App, HttpResponse, HttpServer,
+use ogc_core::{
+    selector::Selectors,
+    sqlx_postgres::{account::ensure_account, connect_and_migrate},
 };
+
+use actix_web::{middleware, rt::System, web::Data, App, HttpServer};
 use ansi_term::Colour;
+use anyhow::anyhow;
 use chrono::Utc;
 use clap::{Parser, ValueHint};
-use fantoccini::{ClientBuilder, Locator};
-use log::{logger, Level};
-use tokio::{
-    sync::{Mutex, RwLock},
-    time::{sleep, Duration},
-};
+use log::Level;
+use tokio::sync::{broadcast, mpsc, watch};
+
+/// bot events buffered for a slow event stream subscriber
+const EVENT_CAPACITY: usize = 64;
+
+/// commands queued while the bot is refreshing
+const COMMAND_CAPACITY: usize = 16;
 
 #[derive(Debug, Parser)]
 pub struct Opts {
@@ -33,16 +41,35 @@ pub struct Opts {
     #[clap(long, default_value = "127.0.0.1:3000", env = "OGC_HOST")]
     host: String,
 
+    /// gRPC listening address in "${HOST}:${PORT}" format.
+    #[clap(long, default_value = "127.0.0.1:50051", env = "OGC_GRPC_HOST")]
+    grpc_host: SocketAddr,
+
     #[clap(long, env = "OGC_JWT_PRIV_FILE", value_parser, value_hint = ValueHint::FilePath)]
     jwt_priv_file: Option<PathBuf>,
 
     #[clap(long, env = "OGC_JWT_PUB_FILE", value_parser, value_hint = ValueHint::FilePath)]
     jwt_pub_file: Option<PathBuf>,
+
+    /// Bearer token of the Prometheus scraper, `/metrics` is not served without one.
+    #[clap(long, env = "OGC_METRICS_TOKEN")]
+    metrics_token: Option<String>,
 }
 
 pub fn run(shared: SharedParams, opts: Opts) -> anyhow::Result<()> {
     init_logger("warn,oracle-core=info,oracle=info", true);
 
+    // the api exposes the account, refuse to serve it without authentication
+    let (Some(jwt_priv_file), Some(jwt_pub_file)) = (&opts.jwt_priv_file, &opts.jwt_pub_file)
+    else {
+        return Err(anyhow!(
+            "--jwt-priv-file and --jwt-pub-file are required to serve the api"
+        ));
+    };
+    let jwt_keys = Data::new(JwtKeys::load(jwt_priv_file, jwt_pub_file)?);
+
+    crate::metrics::init();
+
     let system = System::new();
     let pg_pool = system.block_on(connect_and_migrate(&shared.database_url, 5))?;
 
@@ -50,58 +77,89 @@ pub fn run(shared: SharedParams, opts: Opts) -> anyhow::Result<()> {
         let config_path = shared
             .config_path
             .unwrap_or("./deployment//dev.toml".into());
-        let Config { user, planets_info } = Config::load(&config_path)?;
-
-        // // start cheat bot for empire data concurrently
-        // let _cheat_pg_pool = pg_pool.clone();
-
-        let bot = CheatBot::new(shared.webdriver_url.as_deref(), planets_info).await?;
-
-        bot.start(&user.account, &user.password).await?;
+        let Config {
+            user,
+            server,
+            planets_info,
+            selectors,
+            scrape_mode,
+            transport,
+            fleet_save,
+        } = Config::load(&config_path)?;
+        let settings = BotSettings {
+            scrape_mode,
+            fleet_save,
+            timezone: server.timezone()?,
+        };
+        let selectors = Arc::new(Selectors::load(selectors.as_ref())?);
+        let transport = transport::connect(
+            transport,
+            shared.webdriver_url.as_deref(),
+            selectors.clone(),
+            server,
+        )
+        .await?;
+
+        let account_id = ensure_account(&pg_pool, &user.account, &user.universe).await?;
+        let overview = SharedOverview::default();
+        let (events, _) = broadcast::channel(EVENT_CAPACITY);
+        let (status, status_rx) = watch::channel(BotStatus {
+            state: BotState::LoggingIn,
+            changed_at: Utc::now(),
+            next_refresh_at: None,
+        });
+        let (commands, commands_rx) = mpsc::channel(COMMAND_CAPACITY);
+
+        // start cheat bot for empire data concurrently
+        let cheat_pg_pool = pg_pool.clone();
+
+        let bot = CheatBot::new(
+            transport,
+            planets_info,
+            selectors,
+            settings,
+            cheat_pg_pool,
+            account_id,
+            BotHandles {
+                overview: overview.clone(),
+                events: events.clone(),
+                status: Arc::new(status),
+            },
+        );
+
+        let state = ApiState {
+            pg_pool,
+            account_id: AccountId(account_id),
+            user: user.clone(),
+            jwt_keys,
+            metrics_token: MetricsToken(opts.metrics_token.clone()),
+            overview,
+            events,
+            status: status_rx,
+            commands,
+        };
+
+        tokio::select! {
+            res = bot.start(&user.account, &user.password, commands_rx) => res?,
+            res = build_http_service(&opts.host, state.clone()) => res?,
+            res = crate::grpc::serve(opts.grpc_host, state) => res?,
+        }
         log::info!("done.");
 
         Ok(())
-        // build_http_service(&opts.host, pg_pool, generation_engine, grpc_client)
-        //     .await
-        //     .map_err(Into::into)
     })
 }
 
-// async fn build_http_service(
-//     host: &str,
-//     pg_pool: PgPool,
-//     generation_engine: Arc<RwLock<GenerationEgine>>,
-//     grpc_client: GrpcClient,
-// ) -> std::io::Result<()> {
-//     HttpServer::new(move || {
-//         App::new()
-//             .app_data(Data::new(pg_pool.clone()))
-//             .app_data(Data::new(generation_engine.clone()))
-//             .app_data(Data::new(Mutex::new(grpc_client.clone())))
-//             .wrap(middleware::Logger::default())
-//             .route("/healthz", web::get().to(HttpResponse::Ok))
-//             .service(
-//                 web::scope("secure")
-//                     .wrap(
-//                         Cors::default()
-//                             .allowed_origin("http://localhost:1234")
-//                             .allowed_origin("http://127.0.0.1:1234")
-//                             .allowed_methods(vec!["POST", "GET", "PUT", "DELETE"])
-//                             .allowed_headers(vec![
-//                                 header::CONTENT_TYPE,
-//                                 header::AUTHORIZATION,
-//                                 header::ACCEPT,
-//                             ])
-//                             .max_age(3600)
-//                             .supports_credentials(),
-//                     )
-//                     .configure(crate::api::routes("api")),
-//             )
-//     })
-//     .bind(host)?
-//     .run()
-//     .await
-// }
+async fn build_http_service(host: &str, state: ApiState) -> std::io::Result<()> {
+    HttpServer::new(move || {
+        App::new()
+            .wrap(middleware::Logger::default())
+            .configure(crate::api::configure(state.clone()))
+    })
+    .bind(host)?
+    .run()
+    .await
+}
 
 pub fn init_logger(pattern: &str, deep: bool) {
     let mut builder = env_logger::Builder::new();
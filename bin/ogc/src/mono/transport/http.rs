@@ -0,0 +1,337 @@
+use super::{Server, Tab, Transport, Urls};
+use crate::mono::{
+    dispatch::{Dispatch, DispatchPage, FleetOrder},
+    fleet_save::FleetSaveStrategy,
+};
+
+use std::sync::{Arc, Mutex};
+
+use ogc_core::{coordinates::Coordinates, selector::Selectors};
+
+use anyhow::{anyhow, Context};
+use async_trait::async_trait;
+use reqwest::{Client, RequestBuilder, StatusCode};
+use serde::{Deserialize, Serialize};
+
+// ids of OGame on the gameforge platform, the same in every community
+const GAME_ENVIRONMENT_ID: &str = "0a31d605-ffaf-43e7-aa02-d06df7116fc8";
+const PLATFORM_GAME_ID: &str = "1dfd8e7e-6e1a-4eb1-8c64-03c3b62efd2f";
+
+/// the game serves a stripped page to unknown clients
+const USER_AGENT: &str = "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/107.0.0.0 Safari/537.36";
+
+/// a headless session calling the lobby api and the game pages directly,
+/// cookies of the game session are kept by the client
+#[derive(Debug)]
+pub struct HttpTransport {
+    client: Client,
+    selectors: Arc<Selectors>,
+    server: Server,
+    urls: Urls,
+    lobby: Mutex<Option<LobbySession>>,
+}
+
+/// what is needed to enter the game again without logging in
+#[derive(Clone, Debug)]
+struct LobbySession {
+    token: String,
+    account: LobbyAccount,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Credentials<'a> {
+    identity: &'a str,
+    password: &'a str,
+    locale: &'a str,
+    gf_lang: &'a str,
+    platform_game_id: &'a str,
+    game_environment_id: &'a str,
+    auto_game_account_creation: bool,
+}
+
+#[derive(Deserialize, Debug)]
+struct AuthToken {
+    token: String,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+struct LobbyAccount {
+    id: i64,
+    server: LobbyServer,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+struct LobbyServer {
+    language: String,
+    number: u32,
+}
+
+#[derive(Deserialize, Debug)]
+struct LoginLink {
+    url: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct SendFleetResponse {
+    success: bool,
+    #[serde(default)]
+    errors: Vec<SendFleetError>,
+}
+
+#[derive(Deserialize, Debug)]
+struct SendFleetError {
+    message: String,
+}
+
+impl HttpTransport {
+    pub fn new(selectors: Arc<Selectors>, server: Server) -> anyhow::Result<Self> {
+        let client = Client::builder()
+            .cookie_store(true)
+            .user_agent(USER_AGENT)
+            .build()?;
+
+        Ok(Self {
+            client,
+            selectors,
+            urls: server.urls(),
+            server,
+            lobby: Mutex::new(None),
+        })
+    }
+
+    async fn text(&self, request: RequestBuilder) -> anyhow::Result<String> {
+        Ok(request.send().await?.error_for_status()?.text().await?)
+    }
+
+    async fn game_page(&self, query: &str) -> anyhow::Result<String> {
+        self.text(self.client.get(format!("{}?{}", self.urls.game, query)))
+            .await
+    }
+
+    /// select a planet or moon on the fleet dispatch
+    async fn dispatch_page(&self, planet_id: &str) -> anyhow::Result<DispatchPage> {
+        let source = self
+            .game_page(&format!(
+                "page=ingame&component=fleetdispatch&cp={}",
+                planet_id
+            ))
+            .await?;
+
+        DispatchPage::parse(&source, &self.selectors)
+    }
+
+    /// post the fleet dispatch form of the selected planet or moon
+    async fn send_fleet(&self, token: &str, order: &FleetOrder) -> anyhow::Result<()> {
+        let destination = order.destination;
+        let mut form = order
+            .ships
+            .iter()
+            .map(|(id, amount)| (format!("am{}", id), amount.to_string()))
+            .collect::<Vec<_>>();
+        form.extend(
+            [
+                ("token", token.to_owned()),
+                ("galaxy", destination.galaxy.to_string()),
+                ("system", destination.system.to_string()),
+                ("position", destination.position.to_string()),
+                ("type", destination.body.id().to_string()),
+                ("metal", order.load.metal.to_string()),
+                ("crystal", order.load.crystal.to_string()),
+                ("deuterium", order.load.deuterium.to_string()),
+                ("food", "0".to_owned()),
+                ("mission", order.mission.to_string()),
+                ("speed", order.speed.to_string()),
+                ("retreatAfterDefenderRetreat", "0".to_owned()),
+                ("lootFoodOnAttack", "0".to_owned()),
+                ("union", "0".to_owned()),
+                ("holdingtime", "1".to_owned()),
+            ]
+            .map(|(key, value)| (key.to_owned(), value)),
+        );
+
+        let response: SendFleetResponse = self
+            .client
+            .post(format!(
+                "{}?page=ingame&component=fleetdispatch&action=sendFleet&ajax=1&asJson=1",
+                self.urls.game
+            ))
+            .header("X-Requested-With", "XMLHttpRequest")
+            .form(&form)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+            .context("send fleet")?;
+        if !response.success {
+            let errors = response
+                .errors
+                .into_iter()
+                .map(|error| error.message)
+                .collect::<Vec<_>>();
+            return Err(anyhow!("send fleet error: {}", errors.join(", ")));
+        }
+
+        Ok(())
+    }
+
+    /// follow the login link of the lobby, which opens a game session
+    async fn enter_game(&self, session: &LobbySession) -> anyhow::Result<()> {
+        let link: LoginLink = self
+            .client
+            .get(format!("{}/users/me/loginLink", self.urls.lobby_api))
+            .bearer_auth(&session.token)
+            .query(&[
+                ("id", session.account.id.to_string()),
+                ("server[language]", session.account.server.language.clone()),
+                ("server[number]", session.account.server.number.to_string()),
+                ("clickedButton", "account_list".to_owned()),
+            ])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        self.text(self.client.get(link.url)).await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Transport for HttpTransport {
+    async fn login(&self, account: &str, password: &str) -> anyhow::Result<()> {
+        let response = self
+            .client
+            .post(&self.urls.auth)
+            .json(&Credentials {
+                identity: account,
+                password,
+                locale: &self.server.locale,
+                gf_lang: &self.server.language,
+                platform_game_id: PLATFORM_GAME_ID,
+                game_environment_id: GAME_ENVIRONMENT_ID,
+                auto_game_account_creation: false,
+            })
+            .send()
+            .await?;
+        if response.status() == StatusCode::CONFLICT {
+            return Err(anyhow!(
+                "login requires solving a captcha, log in once with a browser"
+            ));
+        }
+        let AuthToken { token } = response.error_for_status()?.json().await?;
+
+        let accounts: Vec<LobbyAccount> = self
+            .client
+            .get(format!("{}/users/me/accounts", self.urls.lobby_api))
+            .bearer_auth(&token)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let account = accounts
+            .into_iter()
+            .find(|account| {
+                account.server.number == self.server.number
+                    && account.server.language == self.server.language
+            })
+            .ok_or_else(|| anyhow!("no account in universe {}", self.server.universe()))?;
+
+        let session = LobbySession { token, account };
+        self.enter_game(&session).await?;
+        *self.lobby.lock().expect("lobby session lock") = Some(session);
+
+        Ok(())
+    }
+
+    async fn resume_play(&self) -> anyhow::Result<()> {
+        let session = self
+            .lobby
+            .lock()
+            .expect("lobby session lock")
+            .clone()
+            .ok_or_else(|| anyhow!("not logged in"))?;
+
+        self.enter_game(&session).await
+    }
+
+    async fn overview(&self, planet_id: &str) -> anyhow::Result<String> {
+        self.game_page(&format!("page=ingame&component=overview&cp={}", planet_id))
+            .await
+    }
+
+    async fn planet_list(&self) -> anyhow::Result<String> {
+        self.game_page("page=ingame&component=overview").await
+    }
+
+    // the game remembers the selected planet or moon in the session
+    async fn tab(&self, tab: Tab) -> anyhow::Result<String> {
+        self.game_page(&format!("page=ingame&component={}", tab.component()))
+            .await
+    }
+
+    async fn empire(&self, planet_type: u8) -> anyhow::Result<String> {
+        self.game_page(&format!(
+            "page=standalone&component=empire&planetType={}",
+            planet_type
+        ))
+        .await
+    }
+
+    async fn event_list(&self) -> anyhow::Result<Option<String>> {
+        let source = self
+            .text(
+                self.client
+                    .get(format!(
+                        "{}?page=componentOnly&component=eventList&ajax=1",
+                        self.urls.game
+                    ))
+                    .header("X-Requested-With", "XMLHttpRequest"),
+            )
+            .await?;
+
+        Ok(Some(source))
+    }
+
+    async fn fleet_save(
+        &self,
+        planet_id: &str,
+        location: &Coordinates,
+        strategy: &FleetSaveStrategy,
+    ) -> anyhow::Result<()> {
+        let page = self.dispatch_page(planet_id).await?;
+        let order = page.fleet_save_order(location, strategy)?;
+
+        self.send_fleet(&page.dispatch.token, &order).await
+    }
+
+    async fn dispatch(
+        &self,
+        planet_id: &str,
+        location: &Coordinates,
+        dispatch: &Dispatch,
+    ) -> anyhow::Result<()> {
+        let page = self.dispatch_page(planet_id).await?;
+        let order = page.dispatch_order(location, dispatch)?;
+
+        self.send_fleet(&page.dispatch.token, &order).await
+    }
+
+    async fn movement(&self) -> anyhow::Result<String> {
+        self.game_page("page=ingame&component=movement").await
+    }
+
+    // the recall link of the movement page
+    async fn recall(&self, fleet_id: &str) -> anyhow::Result<()> {
+        self.game_page(&format!(
+            "page=ingame&component=movement&return={}",
+            fleet_id
+        ))
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,196 @@
+pub mod http;
+pub mod webdriver;
+
+use self::{http::HttpTransport, webdriver::WebDriverTransport};
+use super::{dispatch::Dispatch, fleet_save::FleetSaveStrategy};
+
+use std::{fmt, sync::Arc};
+
+use ogc_core::{coordinates::Coordinates, selector::Selectors};
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use chrono::{DateTime, FixedOffset, Offset};
+use serde::{Deserialize, Serialize};
+
+/// the universe the bot plays in, every url is derived from it
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Server {
+    /// e.g. 144 of `s144-tw`
+    pub number: u32,
+    /// community of the universe, e.g. tw of `s144-tw`
+    pub language: String,
+    /// language of the lobby, e.g. `zh_TW`
+    pub locale: String,
+    /// timezone of the server clock, e.g. `+08:00`
+    #[serde(default = "default_utc_offset")]
+    pub utc_offset: String,
+    /// replace the derived urls, e.g. with a local mock server
+    #[serde(default)]
+    pub urls: Option<Urls>,
+}
+
+impl Default for Server {
+    fn default() -> Self {
+        Server {
+            number: 144,
+            language: "tw".to_owned(),
+            locale: "zh_TW".to_owned(),
+            utc_offset: default_utc_offset(),
+            urls: None,
+        }
+    }
+}
+
+impl Server {
+    /// name of the universe, e.g. `s144-tw`
+    pub fn universe(&self) -> String {
+        format!("s{}-{}", self.number, self.language)
+    }
+
+    /// the offset of `utc_offset`, the game shows every time on the server clock
+    pub fn timezone(&self) -> anyhow::Result<FixedOffset> {
+        DateTime::parse_from_str(
+            &format!("1970-01-01 00:00:00 {}", self.utc_offset),
+            "%Y-%m-%d %H:%M:%S %:z",
+        )
+        .map(|time| time.offset().fix())
+        .map_err(|_| anyhow!("invalid utc offset {}, e.g. +08:00", self.utc_offset))
+    }
+
+    pub fn urls(&self) -> Urls {
+        self.urls.clone().unwrap_or_else(|| Urls {
+            lobby: format!("https://lobby.ogame.gameforge.com/{}/", self.locale),
+            lobby_api: "https://lobby.ogame.gameforge.com/api".to_owned(),
+            auth: "https://gameforge.com/api/v1/auth/thirdparty".to_owned(),
+            game: format!(
+                "https://{}.ogame.gameforge.com/game/index.php",
+                self.universe()
+            ),
+        })
+    }
+}
+
+/// the servers of tw are on taipei time
+fn default_utc_offset() -> String {
+    "+08:00".to_owned()
+}
+
+/// where the lobby and the game are served
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Urls {
+    /// login page of the lobby
+    pub lobby: String,
+    pub lobby_api: String,
+    /// gameforge account login
+    pub auth: String,
+    /// `index.php` of the universe
+    pub game: String,
+}
+
+/// how the bot talks to the game
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum TransportKind {
+    /// drive a browser through a WebDriver, e.g. ChromeDriver
+    #[default]
+    WebDriver,
+    /// call the lobby api and the game pages with a plain http session
+    Http,
+}
+
+/// a tab of the game menu
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Tab {
+    Supplies,
+    Facilities,
+    Research,
+    Defence,
+    Fleet,
+}
+
+impl Tab {
+    /// `component` of the game page
+    fn component(self) -> &'static str {
+        match self {
+            Tab::Supplies => "supplies",
+            Tab::Facilities => "facilities",
+            Tab::Research => "research",
+            Tab::Defence => "defenses",
+            Tab::Fleet => "fleetdispatch",
+        }
+    }
+}
+
+/// every interaction the bot has with the game, pages are returned as their source
+/// and parsed by `ogc_core::parser`
+#[async_trait]
+pub trait Transport: fmt::Debug + Send + Sync {
+    /// log into the lobby and enter the universe the account played last
+    async fn login(&self, account: &str, password: &str) -> anyhow::Result<()>;
+
+    /// enter the universe again from the lobby of a logged in account
+    #[allow(dead_code)]
+    async fn resume_play(&self) -> anyhow::Result<()>;
+
+    /// select a planet or moon and return the source of its overview
+    async fn overview(&self, planet_id: &str) -> anyhow::Result<String>;
+
+    /// source of a game page showing the planet list
+    async fn planet_list(&self) -> anyhow::Result<String>;
+
+    /// source of a tab of the selected planet or moon
+    async fn tab(&self, tab: Tab) -> anyhow::Result<String>;
+
+    /// source of the empire page listing every planet or every moon, see `planetType`
+    async fn empire(&self, planet_type: u8) -> anyhow::Result<String>;
+
+    /// source holding the event list, none if the game shows no fleet under way, an error if the
+    /// list could not be read
+    async fn event_list(&self) -> anyhow::Result<Option<String>>;
+
+    /// send every ship of a planet or moon away by `strategy`, an error tells the strategy is
+    /// unavailable, e.g. no free fleet slot
+    async fn fleet_save(
+        &self,
+        planet_id: &str,
+        location: &Coordinates,
+        strategy: &FleetSaveStrategy,
+    ) -> anyhow::Result<()>;
+
+    /// send a fleet of a planet or moon as commanded, every amount is checked first
+    async fn dispatch(
+        &self,
+        planet_id: &str,
+        location: &Coordinates,
+        dispatch: &Dispatch,
+    ) -> anyhow::Result<()>;
+
+    /// source of the fleet movement page listing the fleets under way
+    async fn movement(&self) -> anyhow::Result<String>;
+
+    /// call back a fleet under way by its id of the movement page
+    async fn recall(&self, fleet_id: &str) -> anyhow::Result<()>;
+}
+
+/// connect the transport chosen by the config, the webdriver defaults to `http://localhost:9515`
+pub async fn connect(
+    kind: TransportKind,
+    webdriver_url: Option<&str>,
+    selectors: Arc<Selectors>,
+    server: Server,
+) -> anyhow::Result<Arc<dyn Transport>> {
+    Ok(match kind {
+        TransportKind::WebDriver => Arc::new(
+            WebDriverTransport::connect(
+                webdriver_url.unwrap_or("http://localhost:9515"),
+                selectors,
+                server.urls(),
+            )
+            .await?,
+        ),
+        TransportKind::Http => Arc::new(HttpTransport::new(selectors, server)?),
+    })
+}
@@ -0,0 +1,329 @@
+use super::{Tab, Transport, Urls};
+use crate::mono::{
+    dispatch::{Dispatch, DispatchPage, FleetOrder},
+    fleet_save::FleetSaveStrategy,
+};
+
+use std::sync::Arc;
+
+use ogc_core::{
+    coordinates::{BodyType, Coordinates},
+    selector::Selectors,
+};
+
+use anyhow::{anyhow, Context};
+use async_trait::async_trait;
+use fantoccini::{Client, ClientBuilder, Locator};
+use tokio::time::{sleep, Duration};
+
+/// a browser driven through a WebDriver, it clicks through the pages like a player
+#[derive(Clone, Debug)]
+pub struct WebDriverTransport {
+    client: Client,
+    selectors: Arc<Selectors>,
+    urls: Urls,
+}
+
+impl WebDriverTransport {
+    pub async fn connect(
+        webdriver_url: &str,
+        selectors: Arc<Selectors>,
+        urls: Urls,
+    ) -> anyhow::Result<Self> {
+        let client = ClientBuilder::native().connect(webdriver_url).await?;
+
+        Ok(Self {
+            client,
+            selectors,
+            urls,
+        })
+    }
+
+    async fn click(&self, xpath: &str) -> anyhow::Result<()> {
+        self.client
+            .wait()
+            .for_element(Locator::XPath(xpath))
+            .await?
+            .click()
+            .await?;
+
+        Ok(())
+    }
+
+    /// source of the current page once `ready` is shown
+    async fn source(&self, ready: &str) -> anyhow::Result<String> {
+        self.client
+            .wait()
+            .for_element(Locator::XPath(ready))
+            .await?;
+
+        Ok(self.client.source().await?)
+    }
+
+    /// replace the value of the input at `xpath` with `text`
+    async fn fill(&self, xpath: &str, text: &str) -> anyhow::Result<()> {
+        let input = self
+            .client
+            .wait()
+            .for_element(Locator::XPath(xpath))
+            .await?;
+        input.clear().await?;
+        input.send_keys(text).await?;
+
+        Ok(())
+    }
+
+    /// open the fleet dispatch of a planet or moon
+    async fn dispatch_page(&self, planet_id: &str) -> anyhow::Result<DispatchPage> {
+        let url = format!(
+            "{}?page=ingame&component=fleetdispatch&cp={}",
+            self.urls.game, planet_id
+        );
+        self.client.goto(&url).await?;
+
+        let source = self.source(&self.selectors.fleet_dispatch.next).await?;
+        DispatchPage::parse(&source, &self.selectors)
+    }
+
+    /// fill both steps of the opened fleet dispatch with `order` and send it
+    async fn send_fleet(&self, order: &FleetOrder) -> anyhow::Result<()> {
+        let selectors = &self.selectors.fleet_dispatch;
+        for (id, amount) in &order.ships {
+            let input = format!("{}[@data-technology='{}']//input", selectors.ships, id);
+            self.fill(&input, &amount.to_string()).await?;
+        }
+
+        // next step
+        self.click(&selectors.next).await?;
+
+        sleep(Duration::from_secs(3)).await;
+
+        let destination = order.destination;
+        self.fill(&selectors.galaxy, &destination.galaxy.to_string())
+            .await?;
+        self.fill(&selectors.system, &destination.system.to_string())
+            .await?;
+        self.fill(&selectors.position, &destination.position.to_string())
+            .await?;
+        self.click(match destination.body {
+            BodyType::Planet => &selectors.planet_target,
+            BodyType::Moon => &selectors.moon_target,
+            BodyType::Debris => &selectors.debris_target,
+        })
+        .await?;
+
+        self.click(&format!(
+            "{}[@id='button{}']/a",
+            selectors.missions, order.mission
+        ))
+        .await?;
+        self.click(&format!("{}[{}]", selectors.speed_steps, order.speed))
+            .await?;
+
+        let load = order.load;
+        self.fill(&selectors.metal, &load.metal.to_string()).await?;
+        self.fill(&selectors.crystal, &load.crystal.to_string())
+            .await?;
+        self.fill(&selectors.deuterium, &load.deuterium.to_string())
+            .await?;
+
+        // dispatch fleets, the game answers with a notice
+        self.click(&selectors.send).await?;
+        let notice = self
+            .client
+            .wait()
+            .at_most(Duration::from_secs(10))
+            .for_element(Locator::XPath(&selectors.notice))
+            .await
+            .context("no answer to the sent fleet")?;
+        let message = notice.text().await?;
+        if self
+            .client
+            .find(Locator::XPath(&selectors.refused))
+            .await
+            .is_ok()
+        {
+            return Err(anyhow!("fleet refused: {}", message.trim()));
+        }
+
+        Ok(())
+    }
+
+    /// click last time played in the lobby and switch to the game window
+    async fn enter_game(&self) -> anyhow::Result<()> {
+        self.click(&self.selectors.login.last_played).await?;
+
+        // change current window to new window(game)
+        let windows = self.client.windows().await?;
+        self.client.switch_to_window(windows[1].clone()).await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Transport for WebDriverTransport {
+    async fn login(&self, account: &str, password: &str) -> anyhow::Result<()> {
+        let selectors = &self.selectors.login;
+        // go to the Ogame home page
+        self.client.goto(&self.urls.lobby).await?;
+
+        // click accept cookie button so that modal will not block content
+        self.click(&selectors.cookie_banner).await?;
+
+        // select login tab
+        self.click(&selectors.login_tab).await?;
+
+        // enter account and password
+        self.client
+            .wait()
+            .for_element(Locator::XPath(&selectors.email))
+            .await?
+            .send_keys(account)
+            .await?;
+        self.client
+            .wait()
+            .for_element(Locator::XPath(&selectors.password))
+            .await?
+            .send_keys(password)
+            .await?;
+
+        // click login
+        self.click(&selectors.submit).await?;
+
+        //HACK wait so that webpage can change register button to last time played button
+        // duration depends on processing speed of computer
+        sleep(Duration::from_secs(10)).await;
+
+        self.enter_game().await
+    }
+
+    async fn resume_play(&self) -> anyhow::Result<()> {
+        // go to the Ogame home page
+        self.client.goto(&self.urls.lobby).await?;
+
+        self.enter_game().await
+    }
+
+    async fn overview(&self, planet_id: &str) -> anyhow::Result<String> {
+        let url = format!(
+            "{}?page=ingame&component=overview&cp={}",
+            self.urls.game, planet_id
+        );
+        self.client.goto(&url).await?;
+
+        // wait data response from server
+        sleep(Duration::from_secs(2)).await;
+
+        self.source(&self.selectors.overview.position).await
+    }
+
+    async fn planet_list(&self) -> anyhow::Result<String> {
+        let url = format!("{}?page=ingame&component=overview", self.urls.game);
+        self.client.goto(&url).await?;
+
+        self.source(&self.selectors.planet_list.planet).await
+    }
+
+    async fn tab(&self, tab: Tab) -> anyhow::Result<String> {
+        let selectors = &self.selectors;
+        let (menu, ready) = match tab {
+            Tab::Supplies => (
+                &selectors.menu.supplies,
+                &selectors.infrastructure.metal_mine,
+            ),
+            Tab::Facilities => (
+                &selectors.menu.facilities,
+                &selectors.planet_facility.robotics_factory,
+            ),
+            Tab::Research => (
+                &selectors.menu.research,
+                &selectors.technology.energy_technology,
+            ),
+            Tab::Defence => (&selectors.menu.defence, &selectors.defence.rocket_launcher),
+            Tab::Fleet => (&selectors.menu.fleet, &selectors.fleet.boxes),
+        };
+        self.click(menu).await?;
+
+        self.source(ready).await
+    }
+
+    async fn empire(&self, planet_type: u8) -> anyhow::Result<String> {
+        let url = format!(
+            "{}?page=standalone&component=empire&planetType={}",
+            self.urls.game, planet_type
+        );
+        self.client.goto(&url).await?;
+
+        Ok(self.client.source().await?)
+    }
+
+    async fn event_list(&self) -> anyhow::Result<Option<String>> {
+        let selectors = &self.selectors.fleet_event;
+        // wait for page loading
+        sleep(Duration::from_secs(1)).await;
+        // the blank event box stands in for the drop down while no fleet is under way
+        if let Ok(blank) = self.client.find(Locator::XPath(&selectors.blank)).await {
+            if blank.is_displayed().await? {
+                return Ok(None);
+            }
+        }
+
+        // trigger drop for fetching data, a list that does not open is unknown rather than empty
+        self.click(&selectors.open)
+            .await
+            .context("open the event list")?;
+
+        let source = self.source(&selectors.table).await?;
+
+        // close drop down
+        self.click(&selectors.close).await?;
+
+        Ok(Some(source))
+    }
+
+    async fn fleet_save(
+        &self,
+        planet_id: &str,
+        location: &Coordinates,
+        strategy: &FleetSaveStrategy,
+    ) -> anyhow::Result<()> {
+        let page = self.dispatch_page(planet_id).await?;
+        let order = page.fleet_save_order(location, strategy)?;
+
+        self.send_fleet(&order).await
+    }
+
+    async fn dispatch(
+        &self,
+        planet_id: &str,
+        location: &Coordinates,
+        dispatch: &Dispatch,
+    ) -> anyhow::Result<()> {
+        let page = self.dispatch_page(planet_id).await?;
+        let order = page.dispatch_order(location, dispatch)?;
+
+        self.send_fleet(&order).await
+    }
+
+    // the page lists no fleet when none is under way, nothing to wait for
+    async fn movement(&self) -> anyhow::Result<String> {
+        let url = format!("{}?page=ingame&component=movement", self.urls.game);
+        self.client.goto(&url).await?;
+
+        // wait data response from server
+        sleep(Duration::from_secs(2)).await;
+
+        Ok(self.client.source().await?)
+    }
+
+    async fn recall(&self, fleet_id: &str) -> anyhow::Result<()> {
+        let url = format!(
+            "{}?page=ingame&component=movement&return={}",
+            self.urls.game, fleet_id
+        );
+        self.client.goto(&url).await?;
+
+        Ok(())
+    }
+}
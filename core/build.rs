@@ -0,0 +1,8 @@
+// the repository tests in `tests/postgres.rs` need a server, they are only built with `DATABASE_URL`
+fn main() {
+    println!("cargo:rustc-check-cfg=cfg(database_tests)");
+    println!("cargo:rerun-if-env-changed=DATABASE_URL");
+    if std::env::var_os("DATABASE_URL").is_some() {
+        println!("cargo:rustc-cfg=database_tests");
+    }
+}
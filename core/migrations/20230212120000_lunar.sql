@@ -0,0 +1,91 @@
+-- Moons share the coordinates of their parent planet, `location` links them together.
+
+CREATE TABLE IF NOT EXISTS lunars (
+    updated_at TIMESTAMPTZ NOT NULL,
+    location VARCHAR(10) NOT NULL REFERENCES planets(location) PRIMARY KEY,
+    id VARCHAR(20) NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS lunar_resource (
+    updated_at TIMESTAMPTZ NOT NULL,
+    location VARCHAR(10) NOT NULL REFERENCES lunars(location) PRIMARY KEY,
+    metal VARCHAR(42) NOT NULL,
+    crystal VARCHAR(42) NOT NULL,
+    deuterium VARCHAR(42) NOT NULL,
+    energy VARCHAR(42) NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS lunar_facility (
+    updated_at TIMESTAMPTZ NOT NULL,
+    location VARCHAR(10) NOT NULL REFERENCES lunars(location) PRIMARY KEY,
+    robotics_factory NUMERIC NOT NULL,
+    shipyard NUMERIC NOT NULL,
+    lunar_base NUMERIC NOT NULL,
+    sensor_phalanx NUMERIC NOT NULL,
+    jump_gate NUMERIC NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS lunar_fleet (
+    updated_at TIMESTAMPTZ NOT NULL,
+    location VARCHAR(10) NOT NULL REFERENCES lunars(location) PRIMARY KEY,
+    light_fighter NUMERIC NOT NULL,
+    heavy_fighter NUMERIC NOT NULL,
+    cruiser NUMERIC NOT NULL,
+    battleship NUMERIC NOT NULL,
+    battlecruiser NUMERIC NOT NULL,
+    bomber NUMERIC NOT NULL,
+    destroyer NUMERIC NOT NULL,
+    deathstar NUMERIC NOT NULL,
+    reaper NUMERIC NOT NULL,
+    pathfinder NUMERIC NOT NULL,
+    small_cargo_ship NUMERIC NOT NULL,
+    large_cargo_ship NUMERIC NOT NULL,
+    colony_ship NUMERIC NOT NULL,
+    recycler NUMERIC NOT NULL,
+    espionage_probe NUMERIC NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS lunar_resource_history (
+    snapshot_at TIMESTAMPTZ NOT NULL,
+    location VARCHAR(10) NOT NULL REFERENCES lunars(location),
+    metal VARCHAR(42) NOT NULL,
+    crystal VARCHAR(42) NOT NULL,
+    deuterium VARCHAR(42) NOT NULL,
+    energy VARCHAR(42) NOT NULL,
+    PRIMARY KEY (location, snapshot_at)
+);
+
+CREATE TABLE IF NOT EXISTS lunar_facility_history (
+    snapshot_at TIMESTAMPTZ NOT NULL,
+    location VARCHAR(10) NOT NULL REFERENCES lunars(location),
+    robotics_factory NUMERIC NOT NULL,
+    shipyard NUMERIC NOT NULL,
+    lunar_base NUMERIC NOT NULL,
+    sensor_phalanx NUMERIC NOT NULL,
+    jump_gate NUMERIC NOT NULL,
+    PRIMARY KEY (location, snapshot_at)
+);
+
+CREATE TABLE IF NOT EXISTS lunar_fleet_history (
+    snapshot_at TIMESTAMPTZ NOT NULL,
+    location VARCHAR(10) NOT NULL REFERENCES lunars(location),
+    light_fighter NUMERIC NOT NULL,
+    heavy_fighter NUMERIC NOT NULL,
+    cruiser NUMERIC NOT NULL,
+    battleship NUMERIC NOT NULL,
+    battlecruiser NUMERIC NOT NULL,
+    bomber NUMERIC NOT NULL,
+    destroyer NUMERIC NOT NULL,
+    deathstar NUMERIC NOT NULL,
+    reaper NUMERIC NOT NULL,
+    pathfinder NUMERIC NOT NULL,
+    small_cargo_ship NUMERIC NOT NULL,
+    large_cargo_ship NUMERIC NOT NULL,
+    colony_ship NUMERIC NOT NULL,
+    recycler NUMERIC NOT NULL,
+    espionage_probe NUMERIC NOT NULL,
+    PRIMARY KEY (location, snapshot_at)
+);
+
+CREATE INDEX IF NOT EXISTS lunar_resource_history_snapshot_at_idx ON lunar_resource_history (snapshot_at);
+CREATE INDEX IF NOT EXISTS lunar_facility_history_snapshot_at_idx ON lunar_facility_history (snapshot_at);
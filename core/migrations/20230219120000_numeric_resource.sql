@@ -0,0 +1,47 @@
+-- Resources were stored as the displayed text, e.g. "1,234,567" or "1.2Mn",
+-- convert them to numbers so they can be aggregated. With a unit only the last `,` or `.`
+-- separates decimals, e.g. "1.234,5Mrd", the others group digits.
+
+CREATE FUNCTION pg_temp.parse_amount(raw TEXT) RETURNS BIGINT AS $$
+    SELECT CASE
+        WHEN raw ~* '[a-z]' THEN (
+            replace(
+                regexp_replace(regexp_replace(raw, '[^0-9,.-]', '', 'g'), '[,.](?=.*[,.])', '', 'g'),
+                ',',
+                '.'
+            )::NUMERIC
+            * CASE
+                WHEN raw ~* '(bio|tn|t)$' THEN 1000000000000
+                WHEN raw ~* '(mrd|md|bn|b|g)$' THEN 1000000000
+                WHEN raw ~* '(mio|mn|m)$' THEN 1000000
+                WHEN raw ~* 'k$' THEN 1000
+                ELSE 1
+            END
+        )::BIGINT
+        ELSE COALESCE(NULLIF(regexp_replace(raw, '[^0-9-]', '', 'g'), '')::BIGINT, 0)
+    END
+$$ LANGUAGE SQL IMMUTABLE;
+
+ALTER TABLE resource
+    ALTER COLUMN metal TYPE BIGINT USING pg_temp.parse_amount(metal),
+    ALTER COLUMN crystal TYPE BIGINT USING pg_temp.parse_amount(crystal),
+    ALTER COLUMN deuterium TYPE BIGINT USING pg_temp.parse_amount(deuterium),
+    ALTER COLUMN energy TYPE BIGINT USING pg_temp.parse_amount(energy);
+
+ALTER TABLE resource_history
+    ALTER COLUMN metal TYPE BIGINT USING pg_temp.parse_amount(metal),
+    ALTER COLUMN crystal TYPE BIGINT USING pg_temp.parse_amount(crystal),
+    ALTER COLUMN deuterium TYPE BIGINT USING pg_temp.parse_amount(deuterium),
+    ALTER COLUMN energy TYPE BIGINT USING pg_temp.parse_amount(energy);
+
+ALTER TABLE lunar_resource
+    ALTER COLUMN metal TYPE BIGINT USING pg_temp.parse_amount(metal),
+    ALTER COLUMN crystal TYPE BIGINT USING pg_temp.parse_amount(crystal),
+    ALTER COLUMN deuterium TYPE BIGINT USING pg_temp.parse_amount(deuterium),
+    ALTER COLUMN energy TYPE BIGINT USING pg_temp.parse_amount(energy);
+
+ALTER TABLE lunar_resource_history
+    ALTER COLUMN metal TYPE BIGINT USING pg_temp.parse_amount(metal),
+    ALTER COLUMN crystal TYPE BIGINT USING pg_temp.parse_amount(crystal),
+    ALTER COLUMN deuterium TYPE BIGINT USING pg_temp.parse_amount(deuterium),
+    ALTER COLUMN energy TYPE BIGINT USING pg_temp.parse_amount(energy);
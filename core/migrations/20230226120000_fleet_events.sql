@@ -0,0 +1,20 @@
+-- Every fleet event observed in the event list, an event is open while it is still
+-- listed and resolved once it disappears (arrived or recalled).
+
+CREATE TABLE IF NOT EXISTS fleet_events (
+    id BIGSERIAL PRIMARY KEY,
+    mission_type VARCHAR(42) NOT NULL,
+    coords_origin VARCHAR(42) NOT NULL,
+    dest_coords VARCHAR(42) NOT NULL,
+    arrival_time VARCHAR(42) NOT NULL,
+    first_seen_at TIMESTAMPTZ NOT NULL,
+    last_seen_at TIMESTAMPTZ NOT NULL,
+    resolved_at TIMESTAMPTZ
+);
+
+-- deduplicate open events only, a resolved event is never reopened
+CREATE UNIQUE INDEX IF NOT EXISTS fleet_events_open_idx
+    ON fleet_events (mission_type, coords_origin, dest_coords, arrival_time)
+    WHERE resolved_at IS NULL;
+
+CREATE INDEX IF NOT EXISTS fleet_events_first_seen_at_idx ON fleet_events (first_seen_at);
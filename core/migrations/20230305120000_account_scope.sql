@@ -0,0 +1,65 @@
+-- Scope every table by account and universe so several bots can share one database.
+
+CREATE TABLE IF NOT EXISTS accounts (
+    id SERIAL PRIMARY KEY,
+    account VARCHAR(254) NOT NULL,
+    universe VARCHAR(42) NOT NULL,
+    UNIQUE (account, universe)
+);
+
+-- rows written before scoping are kept under a placeholder account
+INSERT INTO accounts (account, universe)
+SELECT 'unknown', 'unknown'
+WHERE EXISTS (SELECT 1 FROM planets) OR EXISTS (SELECT 1 FROM fleet_events);
+
+DO $$
+DECLARE
+    planet_tables TEXT[] := ARRAY['resource', 'infrastructure', 'facility', 'technology', 'defence', 'fleet', 'lunars'];
+    planet_history_tables TEXT[] := ARRAY['resource_history', 'infrastructure_history', 'facility_history', 'technology_history', 'defence_history', 'fleet_history'];
+    lunar_tables TEXT[] := ARRAY['lunar_resource', 'lunar_facility', 'lunar_fleet'];
+    lunar_history_tables TEXT[] := ARRAY['lunar_resource_history', 'lunar_facility_history', 'lunar_fleet_history'];
+    t TEXT;
+BEGIN
+    -- the column goes last on both current and history tables to keep their column order aligned
+    FOREACH t IN ARRAY ARRAY['planets', 'fleet_events'] || planet_tables || planet_history_tables || lunar_tables || lunar_history_tables LOOP
+        EXECUTE format('ALTER TABLE %I ADD COLUMN account_id INTEGER REFERENCES accounts(id)', t);
+        EXECUTE format('UPDATE %I SET account_id = (SELECT id FROM accounts WHERE account = %L AND universe = %L)', t, 'unknown', 'unknown');
+        EXECUTE format('ALTER TABLE %I ALTER COLUMN account_id SET NOT NULL', t);
+    END LOOP;
+
+    FOREACH t IN ARRAY lunar_tables || lunar_history_tables || planet_tables || planet_history_tables LOOP
+        EXECUTE format('ALTER TABLE %I DROP CONSTRAINT %I', t, t || '_location_fkey');
+    END LOOP;
+
+    ALTER TABLE planets DROP CONSTRAINT planets_pkey;
+    ALTER TABLE planets ADD PRIMARY KEY (account_id, location);
+
+    FOREACH t IN ARRAY planet_tables LOOP
+        EXECUTE format('ALTER TABLE %I DROP CONSTRAINT %I', t, t || '_pkey');
+        EXECUTE format('ALTER TABLE %I ADD PRIMARY KEY (account_id, location)', t);
+        EXECUTE format('ALTER TABLE %I ADD FOREIGN KEY (account_id, location) REFERENCES planets(account_id, location)', t);
+    END LOOP;
+
+    FOREACH t IN ARRAY planet_history_tables LOOP
+        EXECUTE format('ALTER TABLE %I DROP CONSTRAINT %I', t, t || '_pkey');
+        EXECUTE format('ALTER TABLE %I ADD PRIMARY KEY (account_id, location, snapshot_at)', t);
+        EXECUTE format('ALTER TABLE %I ADD FOREIGN KEY (account_id, location) REFERENCES planets(account_id, location)', t);
+    END LOOP;
+
+    FOREACH t IN ARRAY lunar_tables LOOP
+        EXECUTE format('ALTER TABLE %I DROP CONSTRAINT %I', t, t || '_pkey');
+        EXECUTE format('ALTER TABLE %I ADD PRIMARY KEY (account_id, location)', t);
+        EXECUTE format('ALTER TABLE %I ADD FOREIGN KEY (account_id, location) REFERENCES lunars(account_id, location)', t);
+    END LOOP;
+
+    FOREACH t IN ARRAY lunar_history_tables LOOP
+        EXECUTE format('ALTER TABLE %I DROP CONSTRAINT %I', t, t || '_pkey');
+        EXECUTE format('ALTER TABLE %I ADD PRIMARY KEY (account_id, location, snapshot_at)', t);
+        EXECUTE format('ALTER TABLE %I ADD FOREIGN KEY (account_id, location) REFERENCES lunars(account_id, location)', t);
+    END LOOP;
+END $$;
+
+DROP INDEX IF EXISTS fleet_events_open_idx;
+CREATE UNIQUE INDEX IF NOT EXISTS fleet_events_open_idx
+    ON fleet_events (account_id, mission_type, coords_origin, dest_coords, arrival_time)
+    WHERE resolved_at IS NULL;
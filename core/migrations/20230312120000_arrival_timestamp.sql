@@ -0,0 +1,8 @@
+-- Arrival times were kept as the event list shows them, e.g. `12:34:56 時`, without a date or
+-- timezone. They become timestamps, past rows are dated the day they were first seen in UTC.
+
+ALTER TABLE fleet_events ALTER COLUMN arrival_time TYPE TIMESTAMPTZ
+    USING (
+        (first_seen_at AT TIME ZONE 'UTC')::date
+            + COALESCE(substring(arrival_time from '\d{1,2}:\d{2}:\d{2}')::time, '00:00:00'::time)
+    ) AT TIME ZONE 'UTC';
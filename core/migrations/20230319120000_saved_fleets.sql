@@ -0,0 +1,23 @@
+-- Fleets the bot sent away from an attack, a fleet is open until it is recalled or no longer
+-- under way.
+
+CREATE TABLE IF NOT EXISTS saved_fleets (
+    id BIGSERIAL PRIMARY KEY,
+    account_id INTEGER NOT NULL REFERENCES accounts(id),
+    planet_id VARCHAR(42) NOT NULL,
+    location VARCHAR(42) NOT NULL,
+    destination VARCHAR(42) NOT NULL,
+    mission INTEGER NOT NULL,
+    -- the attack the fleet was saved from, as in fleet_events
+    attack_mission_type VARCHAR(42) NOT NULL,
+    attack_coords_origin VARCHAR(42) NOT NULL,
+    attack_dest_coords VARCHAR(42) NOT NULL,
+    attack_arrival_time TIMESTAMPTZ NOT NULL,
+    saved_at TIMESTAMPTZ NOT NULL,
+    -- set once the attack is no longer listed
+    resolved_at TIMESTAMPTZ,
+    closed_at TIMESTAMPTZ,
+    recalled BOOLEAN NOT NULL DEFAULT FALSE
+);
+
+CREATE INDEX IF NOT EXISTS saved_fleets_open_idx ON saved_fleets (account_id) WHERE closed_at IS NULL;
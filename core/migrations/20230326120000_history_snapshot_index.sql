@@ -0,0 +1,5 @@
+-- Every history table is read by time, the fleets and defences were left without an index.
+
+CREATE INDEX IF NOT EXISTS defence_history_snapshot_at_idx ON defence_history (snapshot_at);
+CREATE INDEX IF NOT EXISTS fleet_history_snapshot_at_idx ON fleet_history (snapshot_at);
+CREATE INDEX IF NOT EXISTS lunar_fleet_history_snapshot_at_idx ON lunar_fleet_history (snapshot_at);
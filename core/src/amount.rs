@@ -0,0 +1,322 @@
+use std::{
+    fmt,
+    iter::Sum,
+    ops::{Add, AddAssign, Neg, Sub, SubAssign},
+    str::FromStr,
+};
+
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+use sqlx::{
+    encode::IsNull,
+    error::BoxDynError,
+    postgres::{PgArgumentBuffer, PgTypeInfo, PgValueRef, Postgres},
+    Decode, Encode, Type,
+};
+
+/// amount of a single resource, energy can be negative
+#[derive(
+    Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+#[serde(transparent)]
+pub struct Amount(pub i64);
+
+impl Amount {
+    pub const ZERO: Amount = Amount(0);
+
+    /// multiply by `factor`, rounding toward zero and saturating at the bounds
+    pub fn scale(self, factor: f64) -> Self {
+        Amount((self.0 as f64 * factor) as i64)
+    }
+
+    /// multiply by `factor`, rounding toward zero, none if the product is out of range
+    pub fn checked_scale(self, factor: f64) -> Option<Self> {
+        let product = (self.0 as f64 * factor).trunc();
+        // `i64::MAX as f64` rounds up to 2^63, which is already out of range
+        (product.is_finite() && product >= i64::MIN as f64 && product < i64::MAX as f64)
+            .then_some(Amount(product as i64))
+    }
+
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        self.0.checked_add(rhs.0).map(Amount)
+    }
+
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        self.0.checked_sub(rhs.0).map(Amount)
+    }
+
+    pub fn is_negative(self) -> bool {
+        self.0 < 0
+    }
+}
+
+impl FromStr for Amount {
+    type Err = anyhow::Error;
+
+    /// parse a displayed amount, e.g. `1,234,567`, `1.234.567`, `-120`, `1.2Mn` or `3,5Mrd`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let text: String = s
+            .chars()
+            .filter(|c| !c.is_whitespace() && *c != '\'' && *c != '_')
+            .collect();
+
+        let (negative, text) = match text.strip_prefix(['-', '−']) {
+            Some(rest) => (true, rest),
+            None => (false, text.strip_prefix('+').unwrap_or(&text)),
+        };
+
+        let suffix_start = text.find(|c: char| c.is_alphabetic()).unwrap_or(text.len());
+        let (number, suffix) = text.split_at(suffix_start);
+
+        if number.is_empty()
+            || !number
+                .chars()
+                .all(|c| c.is_ascii_digit() || c == ',' || c == '.')
+        {
+            return Err(anyhow!("invalid amount: {}", s));
+        }
+
+        let overflow = || anyhow!("amount overflow: {}", s);
+        let value = if suffix.is_empty() {
+            // without an abbreviation every separator is a thousands separator
+            number
+                .chars()
+                .filter(char::is_ascii_digit)
+                .collect::<String>()
+                .parse::<i128>()
+                .map_err(|_| overflow())?
+        } else {
+            let multiplier = abbreviation_multiplier(suffix)
+                .ok_or_else(|| anyhow!("unknown amount abbreviation: {}", s))?;
+
+            // an abbreviated amount has at most one decimal separator, either `.` or `,`
+            let (integer, fraction) = match number.rfind(['.', ',']) {
+                Some(i) => (&number[..i], &number[i + 1..]),
+                None => (number, ""),
+            };
+            let integer = integer
+                .chars()
+                .filter(char::is_ascii_digit)
+                .collect::<String>();
+            let integer = if integer.is_empty() {
+                0
+            } else {
+                integer.parse::<i128>().map_err(|_| overflow())?
+            };
+            // digits beyond the multiplier are below one unit
+            let fraction = &fraction[..fraction.len().min(multiplier.ilog10() as usize)];
+            let fraction_value = if fraction.is_empty() {
+                0
+            } else {
+                fraction.parse::<i128>().map_err(|_| overflow())? * multiplier
+                    / 10i128.pow(fraction.len() as u32)
+            };
+
+            integer
+                .checked_mul(multiplier)
+                .and_then(|value| value.checked_add(fraction_value))
+                .ok_or_else(overflow)?
+        };
+
+        let value = if negative { -value } else { value };
+
+        Ok(Amount(i64::try_from(value).map_err(|_| overflow())?))
+    }
+}
+
+/// abbreviations used by the game across locales
+fn abbreviation_multiplier(suffix: &str) -> Option<i128> {
+    match suffix.to_lowercase().as_str() {
+        "k" => Some(1_000),
+        "m" | "mn" | "mio" => Some(1_000_000),
+        "b" | "bn" | "g" | "md" | "mrd" => Some(1_000_000_000),
+        "t" | "tn" | "bio" => Some(1_000_000_000_000),
+        _ => None,
+    }
+}
+
+impl fmt::Display for Amount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl From<i64> for Amount {
+    fn from(value: i64) -> Self {
+        Amount(value)
+    }
+}
+
+// the operators saturate at the bounds, see `checked_add` and `checked_sub` to detect it
+impl Add for Amount {
+    type Output = Amount;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Amount(self.0.saturating_add(rhs.0))
+    }
+}
+
+impl AddAssign for Amount {
+    fn add_assign(&mut self, rhs: Self) {
+        self.0 = self.0.saturating_add(rhs.0);
+    }
+}
+
+impl Sub for Amount {
+    type Output = Amount;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Amount(self.0.saturating_sub(rhs.0))
+    }
+}
+
+impl SubAssign for Amount {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.0 = self.0.saturating_sub(rhs.0);
+    }
+}
+
+impl Neg for Amount {
+    type Output = Amount;
+
+    fn neg(self) -> Self::Output {
+        Amount(self.0.saturating_neg())
+    }
+}
+
+impl Sum for Amount {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Amount::ZERO, Add::add)
+    }
+}
+
+// stored as BIGINT
+impl Type<Postgres> for Amount {
+    fn type_info() -> PgTypeInfo {
+        <i64 as Type<Postgres>>::type_info()
+    }
+}
+
+impl Encode<'_, Postgres> for Amount {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+        <i64 as Encode<Postgres>>::encode_by_ref(&self.0, buf)
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for Amount {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        Ok(Amount(<i64 as Decode<Postgres>>::decode(value)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let cases = [
+            ("0", 0),
+            ("1,234,567", 1_234_567),
+            ("1.234.567", 1_234_567),
+            ("1 234 567", 1_234_567),
+            ("1'234'567", 1_234_567),
+            ("+120", 120),
+            ("-120", -120),
+            ("−1.500", -1_500),
+            ("12k", 12_000),
+            ("1.2Mn", 1_200_000),
+            ("1,2Mn", 1_200_000),
+            ("3,5Mrd", 3_500_000_000),
+            ("2.25Bn", 2_250_000_000),
+            ("1.5Mio", 1_500_000),
+            ("-1.2Mn", -1_200_000),
+            ("0.1234k", 123),
+            ("1.234,5Mrd", 1_234_500_000_000),
+            ("9.223372036854775807T", 9_223_372_036_854),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(
+                text.parse::<Amount>().unwrap(),
+                Amount(expected),
+                "{}",
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn parse_garbage() {
+        for text in [
+            "", "-", "abc", "12x", "1.2Zz", "Mn", "1-2", "1.2Mn3", "0x10",
+        ] {
+            assert!(text.parse::<Amount>().is_err(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn parse_overflow() {
+        for text in [
+            "9,223,372,036,854,775,808",
+            "-9,223,372,036,854,775,809",
+            "9223372036854775807000000000000000000000000",
+            "10000000T",
+            "170141183460469231731687303715884105727T",
+        ] {
+            assert!(text.parse::<Amount>().is_err(), "{}", text);
+        }
+        // digits below one unit are dropped, however many
+        assert_eq!(
+            "1.00000000000000000000000000000000000000000001k"
+                .parse::<Amount>()
+                .unwrap(),
+            Amount(1_000)
+        );
+        assert_eq!(
+            "9,223,372,036,854,775,807".parse::<Amount>().unwrap(),
+            Amount(i64::MAX)
+        );
+        assert_eq!(
+            "-9,223,372,036,854,775,808".parse::<Amount>().unwrap(),
+            Amount(i64::MIN)
+        );
+    }
+
+    #[test]
+    fn arithmetic() {
+        assert_eq!(Amount(5) + Amount(-7), Amount(-2));
+        assert_eq!(Amount(5) - Amount(7), Amount(-2));
+        assert_eq!(-Amount(5), Amount(-5));
+        assert_eq!(Amount(1_000).scale(1.5), Amount(1_500));
+        assert_eq!(Amount(-999).scale(0.5), Amount(-499));
+        assert_eq!(
+            [Amount(1), Amount(2), Amount(3)]
+                .into_iter()
+                .sum::<Amount>(),
+            Amount(6)
+        );
+        assert!(Amount(-1).is_negative());
+        assert!(Amount(2) > Amount(-3));
+    }
+
+    #[test]
+    fn arithmetic_overflow() {
+        assert_eq!(Amount(i64::MAX) + Amount(1), Amount(i64::MAX));
+        assert_eq!(Amount(i64::MIN) - Amount(1), Amount(i64::MIN));
+        assert_eq!(-Amount(i64::MIN), Amount(i64::MAX));
+        assert_eq!(Amount(i64::MAX).scale(2.0), Amount(i64::MAX));
+
+        assert_eq!(Amount(i64::MAX).checked_add(Amount(1)), None);
+        assert_eq!(Amount(i64::MIN).checked_sub(Amount(1)), None);
+        assert_eq!(Amount(1).checked_add(Amount(2)), Some(Amount(3)));
+        assert_eq!(Amount(i64::MAX).checked_scale(2.0), None);
+        assert_eq!(Amount(1).checked_scale(f64::NAN), None);
+        assert_eq!(Amount(1_000).checked_scale(0.25), Some(Amount(250)));
+
+        let mut amount = Amount(i64::MAX - 1);
+        amount += Amount(5);
+        assert_eq!(amount, Amount(i64::MAX));
+        amount -= Amount(i64::MAX);
+        assert_eq!(amount, Amount(0));
+    }
+}
@@ -0,0 +1,182 @@
+use std::{fmt, str::FromStr};
+
+use anyhow::anyhow;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use sqlx::{
+    encode::IsNull,
+    error::BoxDynError,
+    postgres::{PgArgumentBuffer, PgTypeInfo, PgValueRef, Postgres},
+    Decode, Encode, Type,
+};
+
+/// what is at a position of a system
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum BodyType {
+    #[default]
+    Planet,
+    Moon,
+    Debris,
+}
+
+impl BodyType {
+    /// `type` of the fleet dispatch, e.g. 3 for a moon
+    pub fn id(self) -> u8 {
+        match self {
+            BodyType::Planet => 1,
+            BodyType::Debris => 2,
+            BodyType::Moon => 3,
+        }
+    }
+
+    /// from the classes of a planet icon of the event list, e.g. `planetIcon moon`
+    pub fn from_class(class: &str) -> Option<Self> {
+        class.split_whitespace().find_map(|class| match class {
+            "planet" => Some(BodyType::Planet),
+            "moon" => Some(BodyType::Moon),
+            "tf" | "debris" => Some(BodyType::Debris),
+            _ => None,
+        })
+    }
+
+    fn suffix(self) -> Option<&'static str> {
+        match self {
+            BodyType::Planet => None,
+            BodyType::Moon => Some("moon"),
+            BodyType::Debris => Some("debris"),
+        }
+    }
+}
+
+/// a planet, moon or debris field, written as the game shows it, e.g. `[1:234:5]`,
+/// followed by `moon` or `debris` unless it is a planet
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Coordinates {
+    pub galaxy: u32,
+    pub system: u32,
+    pub position: u32,
+    pub body: BodyType,
+}
+
+impl Coordinates {
+    pub fn new(galaxy: u32, system: u32, position: u32, body: BodyType) -> Self {
+        Coordinates {
+            galaxy,
+            system,
+            position,
+            body,
+        }
+    }
+
+    /// the same position with another body, e.g. the moon of a planet
+    pub fn with_body(self, body: BodyType) -> Self {
+        Coordinates { body, ..self }
+    }
+
+    pub fn planet(self) -> Self {
+        self.with_body(BodyType::Planet)
+    }
+
+    pub fn moon(self) -> Self {
+        self.with_body(BodyType::Moon)
+    }
+
+    /// planet, moon and debris field of a position share it
+    pub fn same_position(&self, other: &Coordinates) -> bool {
+        self.planet() == other.planet()
+    }
+
+    /// flight distance to `other`, never shorter than the game takes it in a universe whose
+    /// galaxies and systems wrap around
+    pub fn distance(&self, other: &Coordinates) -> u32 {
+        if self.galaxy != other.galaxy {
+            20_000 * self.galaxy.abs_diff(other.galaxy)
+        } else if self.system != other.system {
+            2_700 + 95 * self.system.abs_diff(other.system)
+        } else if self.position != other.position {
+            1_000 + 5 * self.position.abs_diff(other.position)
+        } else {
+            5
+        }
+    }
+}
+
+impl fmt::Display for Coordinates {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}:{}:{}]", self.galaxy, self.system, self.position)?;
+        match self.body.suffix() {
+            Some(suffix) => write!(f, " {}", suffix),
+            None => Ok(()),
+        }
+    }
+}
+
+impl FromStr for Coordinates {
+    type Err = anyhow::Error;
+
+    /// parse `[1:234:5]`, `1:234:5` or `[1:234:5] moon`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || anyhow!("invalid coordinates: {}", s);
+        let s = s.trim();
+        let (position, body) = match s.split_once(char::is_whitespace) {
+            Some((position, suffix)) => {
+                let body = [BodyType::Planet, BodyType::Moon, BodyType::Debris]
+                    .into_iter()
+                    .find(|body| body.suffix().unwrap_or("planet") == suffix.trim())
+                    .ok_or_else(invalid)?;
+                (position, body)
+            }
+            None => (s, BodyType::Planet),
+        };
+
+        let mut parts = position
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .split(':')
+            .map(|part| part.trim().parse::<u32>());
+        match (parts.next(), parts.next(), parts.next(), parts.next()) {
+            (Some(Ok(galaxy)), Some(Ok(system)), Some(Ok(position)), None) => {
+                Ok(Coordinates::new(galaxy, system, position, body))
+            }
+            _ => Err(invalid()),
+        }
+    }
+}
+
+// serialized as written, e.g. `"[1:234:5] moon"`
+impl Serialize for Coordinates {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Coordinates {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+// stored as written in VARCHAR columns
+impl Type<Postgres> for Coordinates {
+    fn type_info() -> PgTypeInfo {
+        <String as Type<Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        <String as Type<Postgres>>::compatible(ty)
+    }
+}
+
+impl Encode<'_, Postgres> for Coordinates {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+        <String as Encode<Postgres>>::encode_by_ref(&self.to_string(), buf)
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for Coordinates {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        Ok(<&str as Decode<Postgres>>::decode(value)?.parse()?)
+    }
+}
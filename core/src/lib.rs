@@ -1 +1,6 @@
+pub mod amount;
+pub mod coordinates;
+pub mod model;
+pub mod parser;
+pub mod selector;
 pub mod sqlx_postgres;
@@ -0,0 +1,504 @@
+use crate::{amount::Amount, coordinates::Coordinates};
+
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+use std::ops::{Add, Sub};
+use strum_macros::{Display, EnumString};
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Resource {
+    pub metal: Amount,
+    pub crystal: Amount,
+    pub deuterium: Amount,
+    pub energy: Amount,
+}
+
+impl Resource {
+    /// multiply every resource by `factor`, rounding toward zero
+    pub fn scale(&self, factor: f64) -> Self {
+        Resource {
+            metal: self.metal.scale(factor),
+            crystal: self.crystal.scale(factor),
+            deuterium: self.deuterium.scale(factor),
+            energy: self.energy.scale(factor),
+        }
+    }
+
+    /// whether there is enough metal, crystal and deuterium to pay `cost`, energy is ignored
+    pub fn covers(&self, cost: &Resource) -> bool {
+        self.metal >= cost.metal && self.crystal >= cost.crystal && self.deuterium >= cost.deuterium
+    }
+
+    /// metal, crystal and deuterium in total, e.g. the cargo capacity needed to load them
+    pub fn total(&self) -> Amount {
+        self.metal + self.crystal + self.deuterium
+    }
+}
+
+impl Add for Resource {
+    type Output = Resource;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Resource {
+            metal: self.metal + rhs.metal,
+            crystal: self.crystal + rhs.crystal,
+            deuterium: self.deuterium + rhs.deuterium,
+            energy: self.energy + rhs.energy,
+        }
+    }
+}
+
+impl Sub for Resource {
+    type Output = Resource;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Resource {
+            metal: self.metal - rhs.metal,
+            crystal: self.crystal - rhs.crystal,
+            deuterium: self.deuterium - rhs.deuterium,
+            energy: self.energy - rhs.energy,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Infrastructure {
+    pub metal_mine: u32,
+    pub crystal_mine: u32,
+    pub deuterium_synthesizer: u32,
+    pub energy_plant: u32,
+    pub fusion_reactor: u32,
+    pub solar_satellite: u32,
+    pub crawler: u32,
+    pub metal_storage: u32,
+    pub crystal_storage: u32,
+    pub deuterium_tank: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanetFacility {
+    pub robotics_factory: u32,
+    pub shipyard: u32,
+    pub research_lab: u32,
+    pub alliance_depot: u32,
+    pub missile_silo: u32,
+    pub nanite_factory: u32,
+    pub terraformer: u32,
+    pub space_dock: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct LunarFacility {
+    pub robotics_factory: u32,
+    pub shipyard: u32,
+    pub lunar_base: u32,
+    pub sensor_phalanx: u32,
+    pub jump_gate: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Technology {
+    pub energy_technology: u32,
+    pub laser_technology: u32,
+    pub ion_technology: u32,
+    pub hyperspace_technology: u32,
+    pub plasma_technology: u32,
+    pub combustion_drive: u32,
+    pub impulse_drive: u32,
+    pub hyperspace_drive: u32,
+    pub espionage_technology: u32,
+    pub computer_technology: u32,
+    pub astrophysics: u32,
+    pub intergalactic_research_network: u32,
+    pub graviton_technology: u32,
+    pub armour_technology: u32,
+    pub weapons_technology: u32,
+    pub shielding_technology: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Defence {
+    pub rocket_launcher: u32,
+    pub light_laser: u32,
+    pub heavy_laser: u32,
+    pub ion_cannon: u32,
+    pub gauss_cannon: u32,
+    pub plasma_turret: u32,
+    pub small_shield_dome: u32,
+    pub large_shield_dome: u32,
+    pub anti_ballistic_missile: u32,
+    pub interplanetary_missile: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Fleet {
+    pub light_fighter: u32,
+    pub heavy_fighter: u32,
+    pub cruiser: u32,
+    pub battleship: u32,
+    pub battlecruiser: u32,
+    pub bomber: u32,
+    pub destroyer: u32,
+    pub deathstar: u32,
+    pub reaper: u32,
+    pub pathfinder: u32,
+    pub small_cargo_ship: u32,
+    pub large_cargo_ship: u32,
+    pub colony_ship: u32,
+    pub recycler: u32,
+    pub espionage_probe: u32,
+}
+
+impl Fleet {
+    /// amount of every ship keyed by the game's technology id, e.g. `204` for light fighters
+    pub fn units(&self) -> [(u32, u32); 15] {
+        [
+            (204, self.light_fighter),
+            (205, self.heavy_fighter),
+            (206, self.cruiser),
+            (207, self.battleship),
+            (215, self.battlecruiser),
+            (211, self.bomber),
+            (213, self.destroyer),
+            (214, self.deathstar),
+            (218, self.reaper),
+            (219, self.pathfinder),
+            (202, self.small_cargo_ship),
+            (203, self.large_cargo_ship),
+            (208, self.colony_ship),
+            (209, self.recycler),
+            (210, self.espionage_probe),
+        ]
+    }
+
+    pub fn is_zero(&self) -> bool {
+        let sum = self.light_fighter
+            + self.heavy_fighter
+            + self.cruiser
+            + self.battleship
+            + self.battlecruiser
+            + self.bomber
+            + self.destroyer
+            + self.deathstar
+            + self.reaper
+            + self.pathfinder
+            + self.small_cargo_ship
+            + self.large_cargo_ship
+            + self.colony_ship
+            + self.recycler
+            + self.espionage_probe;
+
+        sum == 0
+    }
+
+    pub fn is_not_zero(&self) -> bool {
+        let sum = self.light_fighter
+            + self.heavy_fighter
+            + self.cruiser
+            + self.battleship
+            + self.battlecruiser
+            + self.bomber
+            + self.destroyer
+            + self.deathstar
+            + self.reaper
+            + self.pathfinder
+            + self.small_cargo_ship
+            + self.large_cargo_ship
+            + self.colony_ship
+            + self.recycler
+            + self.espionage_probe;
+
+        sum != 0
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct EmpireOverview {
+    pub overview: Vec<PlanetOverview>,
+    pub technology: Technology,
+    /// none if the event list could not be read, nothing is known about the fleets under way
+    pub maybe_fleet_events: Option<Vec<FleetEvent>>,
+}
+
+/// a planet or moon of the empire overview, see `EmpireOverview::bodies`
+#[derive(Clone, Copy, Debug)]
+pub struct EmpireBody<'a> {
+    pub id: &'a str,
+    pub location: Coordinates,
+    pub fleet: &'a Fleet,
+}
+
+impl EmpireOverview {
+    /// every planet followed by its moon
+    pub fn bodies(&self) -> impl Iterator<Item = EmpireBody<'_>> {
+        self.overview.iter().flat_map(|planet| {
+            let planet_body = EmpireBody {
+                id: &planet.id,
+                location: planet.location,
+                fleet: &planet.fleet,
+            };
+            let lunar_body = planet.lunar.as_ref().map(|lunar| EmpireBody {
+                id: &lunar.id,
+                location: lunar.location,
+                fleet: &lunar.fleet,
+            });
+
+            std::iter::once(planet_body).chain(lunar_body)
+        })
+    }
+
+    /// the planet or moon at `location`, a moon is told apart by its body type
+    pub fn body(&self, location: &Coordinates) -> Option<EmpireBody<'_>> {
+        self.bodies().find(|body| body.location == *location)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanetOverview {
+    pub id: String,
+    pub location: Coordinates,
+    pub resource: Resource,
+    pub infrastructure: Infrastructure,
+    pub facility: PlanetFacility,
+    pub defence: Defence,
+    pub fleet: Fleet,
+    pub lunar: Option<Lunar>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Lunar {
+    pub id: String,
+    pub location: Coordinates,
+    pub resource: Resource,
+    pub facility: LunarFacility,
+    pub fleet: Fleet,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct FleetEvent {
+    pub mission_type: MissionType,
+    /// on the clock of the server
+    pub arrival_time: DateTime<FixedOffset>,
+    pub coords_origin: Coordinates,
+    pub dest_coords: Coordinates,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, EnumString, Display)]
+pub enum MissionType {
+    // Dispatch
+    // Self
+    Expedition,
+    Colonization,
+    Harvesting,
+    Transport,
+    Deployment,
+    Espionage,
+    ACSDefend,
+    Attacking,
+    ACSAttack,
+    Destroy,
+    SearchingForLifeforms,
+    // Return
+    ExpeditionReturn,
+    ColonizationReturn,
+    HarvestingReturn,
+    TransportReturn,
+    DeploymentReturn,
+    EspionageReturn,
+    ACSDefendReturn,
+    AttackingReturn,
+    ACSAttackReturn,
+    DestroyReturn,
+    SearchingForLifeformsReturn,
+    // Ally
+    FriendlyTransport,
+    FriendlyACSDefend,
+    // Enemy
+    EnemyEspionage,
+    EnemyAttacking,
+}
+
+/// whose fleet an event shows, the event list marks it with a css class
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FleetRelation {
+    /// `friendly`
+    Own,
+    /// `neutral`, e.g. a transport of an alliance member
+    Friendly,
+    /// `hostile`
+    Hostile,
+}
+
+impl FleetRelation {
+    /// from the classes of an element, e.g. `hostile textBeefy`
+    pub fn from_class(class: &str) -> Option<Self> {
+        class.split_whitespace().find_map(|class| match class {
+            "friendly" => Some(FleetRelation::Own),
+            "neutral" => Some(FleetRelation::Friendly),
+            "hostile" => Some(FleetRelation::Hostile),
+            _ => None,
+        })
+    }
+}
+
+/// mission ids of the game, e.g. `data-mission-type` of an event row
+pub mod mission {
+    pub const ATTACK: u32 = 1;
+    pub const ACS_ATTACK: u32 = 2;
+    pub const TRANSPORT: u32 = 3;
+    pub const DEPLOYMENT: u32 = 4;
+    pub const ACS_DEFEND: u32 = 5;
+    pub const ESPIONAGE: u32 = 6;
+    pub const COLONIZATION: u32 = 7;
+    pub const HARVESTING: u32 = 8;
+    pub const DESTROY: u32 = 9;
+    pub const EXPEDITION: u32 = 15;
+    pub const SEARCHING_FOR_LIFEFORMS: u32 = 18;
+}
+
+impl MissionType {
+    pub fn from_mission(
+        mission: u32,
+        relation: FleetRelation,
+        return_flight: bool,
+    ) -> anyhow::Result<Self> {
+        use self::mission::*;
+
+        let (outbound, inbound) = match (relation, mission) {
+            (FleetRelation::Hostile, ATTACK | ACS_ATTACK | DESTROY) => {
+                return Ok(MissionType::EnemyAttacking)
+            }
+            (FleetRelation::Hostile, ESPIONAGE) => return Ok(MissionType::EnemyEspionage),
+            (FleetRelation::Friendly, TRANSPORT) => return Ok(MissionType::FriendlyTransport),
+            (FleetRelation::Friendly, ACS_DEFEND) => return Ok(MissionType::FriendlyACSDefend),
+            (FleetRelation::Hostile, _) => {
+                return Err(anyhow::anyhow!("unknown hostile mission {}", mission))
+            }
+            (_, EXPEDITION) => (MissionType::Expedition, MissionType::ExpeditionReturn),
+            (_, COLONIZATION) => (MissionType::Colonization, MissionType::ColonizationReturn),
+            (_, HARVESTING) => (MissionType::Harvesting, MissionType::HarvestingReturn),
+            (_, SEARCHING_FOR_LIFEFORMS) => (
+                MissionType::SearchingForLifeforms,
+                MissionType::SearchingForLifeformsReturn,
+            ),
+            (FleetRelation::Own, TRANSPORT) => {
+                (MissionType::Transport, MissionType::TransportReturn)
+            }
+            (FleetRelation::Own, DEPLOYMENT) => {
+                (MissionType::Deployment, MissionType::DeploymentReturn)
+            }
+            (FleetRelation::Own, ESPIONAGE) => {
+                (MissionType::Espionage, MissionType::EspionageReturn)
+            }
+            (FleetRelation::Own, ACS_DEFEND) => {
+                (MissionType::ACSDefend, MissionType::ACSDefendReturn)
+            }
+            (FleetRelation::Own, ATTACK) => (MissionType::Attacking, MissionType::AttackingReturn),
+            (FleetRelation::Own, ACS_ATTACK) => {
+                (MissionType::ACSAttack, MissionType::ACSAttackReturn)
+            }
+            (FleetRelation::Own, DESTROY) => (MissionType::Destroy, MissionType::DestroyReturn),
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "unknown mission {} of a {:?} fleet",
+                    mission,
+                    relation
+                ))
+            }
+        };
+
+        Ok(if return_flight { inbound } else { outbound })
+    }
+
+    /// from the localized title of the mission icon, e.g. `敵方艦隊 | 攻擊`,
+    /// only for pages without the mission data attributes
+    pub fn from_title(title: &str) -> anyhow::Result<Self> {
+        let unknown = || anyhow::anyhow!("unknown fleet event {}", title);
+        let (relation, mission) = title.split_once('|').ok_or_else(unknown)?;
+        let relation = FLEET_RELATION_TITLES
+            .iter()
+            .find(|(text, _)| *text == relation.trim())
+            .map(|(_, relation)| *relation)
+            .ok_or_else(unknown)?;
+
+        let mission = mission.trim();
+        let (mission, return_flight) = RETURN_MARKS
+            .iter()
+            .find_map(|mark| mission.strip_suffix(mark))
+            .map_or((mission, false), |mission| (mission.trim_end(), true));
+        let mission = MISSION_TITLES
+            .iter()
+            .find(|(text, _)| *text == mission)
+            .map(|(_, id)| *id)
+            .ok_or_else(unknown)?;
+
+        MissionType::from_mission(mission, relation, return_flight)
+    }
+}
+
+/// the part of a mission title before `|`, by language
+const FLEET_RELATION_TITLES: [(&str, FleetRelation); 9] = [
+    // zh_TW
+    ("己方艦隊", FleetRelation::Own),
+    ("友方艦隊", FleetRelation::Friendly),
+    ("敵方艦隊", FleetRelation::Hostile),
+    // en
+    ("Own fleet", FleetRelation::Own),
+    ("Friendly fleet", FleetRelation::Friendly),
+    ("Hostile fleet", FleetRelation::Hostile),
+    // de
+    ("Eigene Flotte", FleetRelation::Own),
+    ("Freundliche Flotte", FleetRelation::Friendly),
+    ("Feindliche Flotte", FleetRelation::Hostile),
+];
+
+/// the part of a mission title after `|`, by language
+const MISSION_TITLES: [(&str, u32); 31] = [
+    // zh_TW
+    ("攻擊", mission::ATTACK),
+    ("ACS聯合攻擊", mission::ACS_ATTACK),
+    ("運輸", mission::TRANSPORT),
+    ("部署", mission::DEPLOYMENT),
+    ("ACS聯合防禦", mission::ACS_DEFEND),
+    ("間諜偵察", mission::ESPIONAGE),
+    ("殖民", mission::COLONIZATION),
+    ("採集回收", mission::HARVESTING),
+    ("摧毀月球", mission::DESTROY),
+    ("遠征探險", mission::EXPEDITION),
+    ("搜索生命形式", mission::SEARCHING_FOR_LIFEFORMS),
+    // en
+    ("Attack", mission::ATTACK),
+    ("ACS Attack", mission::ACS_ATTACK),
+    ("Transport", mission::TRANSPORT),
+    ("Deployment", mission::DEPLOYMENT),
+    ("ACS Defend", mission::ACS_DEFEND),
+    ("Espionage", mission::ESPIONAGE),
+    ("Colonisation", mission::COLONIZATION),
+    ("Harvest", mission::HARVESTING),
+    ("Moon Destruction", mission::DESTROY),
+    ("Expedition", mission::EXPEDITION),
+    ("Lifeform Exploration", mission::SEARCHING_FOR_LIFEFORMS),
+    // de, expedition and transport are spelled as in en
+    ("Angreifen", mission::ATTACK),
+    ("Verbandsangriff", mission::ACS_ATTACK),
+    ("Stationieren", mission::DEPLOYMENT),
+    ("Halten", mission::ACS_DEFEND),
+    ("Spionage", mission::ESPIONAGE),
+    ("Kolonisieren", mission::COLONIZATION),
+    ("Abbauen", mission::HARVESTING),
+    ("Zerstören", mission::DESTROY),
+    ("Lebensformen erforschen", mission::SEARCHING_FOR_LIFEFORMS),
+];
+
+/// suffix of the title of a returning fleet
+const RETURN_MARKS: [&str; 2] = ["(返)", "(R)"];
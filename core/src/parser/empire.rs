@@ -0,0 +1,213 @@
+use super::script::object_literal;
+use crate::{
+    amount::Amount,
+    coordinates::{BodyType, Coordinates},
+    model::{Defence, Fleet, Infrastructure, LunarFacility, PlanetFacility, Resource, Technology},
+};
+
+use std::collections::HashMap;
+
+use anyhow::anyhow;
+use serde_json::Value;
+
+/// javascript call of the empire page which receives every planet as json
+const IMPERIUM_CALL: &str = "createImperiumHtml(";
+
+/// a planet or moon of the empire page, levels and amounts are keyed by the game's technology id
+#[derive(Clone, Debug)]
+pub struct EmpireEntry {
+    pub id: String,
+    /// coordinates of the planet, also for its moon
+    pub location: Coordinates,
+    pub resource: Resource,
+    pub technologies: HashMap<u32, u32>,
+}
+
+/// every planet or every moon listed by the empire page, depending on the `planetType` it was opened with
+pub fn parse_empire(source: &str) -> anyhow::Result<Vec<EmpireEntry>> {
+    let call = source
+        .find(IMPERIUM_CALL)
+        .ok_or_else(|| anyhow!("empire data not found"))?;
+    // the data is the first object literal passed to the call
+    let args = &source[call + IMPERIUM_CALL.len()..];
+    let start = args
+        .find('{')
+        .ok_or_else(|| anyhow!("empire data not found"))?;
+    let json = object_literal(&args[start..])?;
+
+    let data: Value = serde_json::from_str(json)?;
+    let planets = data
+        .get("planets")
+        .and_then(Value::as_array)
+        .ok_or_else(|| anyhow!("empire data has no planets"))?;
+
+    planets.iter().map(entry).collect()
+}
+
+fn entry(planet: &Value) -> anyhow::Result<EmpireEntry> {
+    let id = planet
+        .get("id")
+        .and_then(number)
+        .ok_or_else(|| anyhow!("empire planet without id"))?;
+    let coordinate = |key: &str| {
+        planet
+            .get(key)
+            .and_then(number)
+            .and_then(|value| u32::try_from(value).ok())
+            .ok_or_else(|| anyhow!("empire planet {} without {}", id, key))
+    };
+    let location = Coordinates::new(
+        coordinate("galaxy")?,
+        coordinate("system")?,
+        coordinate("position")?,
+        BodyType::Planet,
+    );
+
+    let amount = |key: &str| {
+        planet
+            .get(key)
+            .and_then(number)
+            .map(Amount)
+            .unwrap_or_default()
+    };
+    let resource = Resource {
+        metal: amount("metal"),
+        crystal: amount("crystal"),
+        deuterium: amount("deuterium"),
+        energy: amount("energy"),
+    };
+
+    // technologies are the keys made of digits only, other keys hold names, html and so on
+    let technologies = planet
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter_map(|(key, value)| {
+            let id = key.parse::<u32>().ok()?;
+            let level = u32::try_from(number(value)?).ok()?;
+            Some((id, level))
+        })
+        .collect();
+
+    Ok(EmpireEntry {
+        id: id.to_string(),
+        location,
+        resource,
+        technologies,
+    })
+}
+
+/// numbers are sometimes sent as formatted strings, e.g. `"1.234"`
+fn number(value: &Value) -> Option<i64> {
+    match value {
+        Value::Number(number) => number
+            .as_i64()
+            .or_else(|| number.as_f64().map(|number| number as i64)),
+        Value::String(text) => text.parse::<Amount>().ok().map(|amount| amount.0),
+        _ => None,
+    }
+}
+
+impl EmpireEntry {
+    fn level(&self, technology_id: u32) -> u32 {
+        self.technologies
+            .get(&technology_id)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub fn infrastructure(&self) -> Infrastructure {
+        Infrastructure {
+            metal_mine: self.level(1),
+            crystal_mine: self.level(2),
+            deuterium_synthesizer: self.level(3),
+            energy_plant: self.level(4),
+            fusion_reactor: self.level(12),
+            solar_satellite: self.level(212),
+            crawler: self.level(217),
+            metal_storage: self.level(22),
+            crystal_storage: self.level(23),
+            deuterium_tank: self.level(24),
+        }
+    }
+
+    pub fn planet_facility(&self) -> PlanetFacility {
+        PlanetFacility {
+            robotics_factory: self.level(14),
+            shipyard: self.level(21),
+            research_lab: self.level(31),
+            alliance_depot: self.level(34),
+            missile_silo: self.level(44),
+            nanite_factory: self.level(15),
+            terraformer: self.level(33),
+            space_dock: self.level(36),
+        }
+    }
+
+    pub fn lunar_facility(&self) -> LunarFacility {
+        LunarFacility {
+            robotics_factory: self.level(14),
+            shipyard: self.level(21),
+            lunar_base: self.level(41),
+            sensor_phalanx: self.level(42),
+            jump_gate: self.level(43),
+        }
+    }
+
+    /// research is account wide, every planet lists the same levels
+    pub fn technology(&self) -> Technology {
+        Technology {
+            energy_technology: self.level(113),
+            laser_technology: self.level(120),
+            ion_technology: self.level(121),
+            hyperspace_technology: self.level(114),
+            plasma_technology: self.level(122),
+            combustion_drive: self.level(115),
+            impulse_drive: self.level(117),
+            hyperspace_drive: self.level(118),
+            espionage_technology: self.level(106),
+            computer_technology: self.level(108),
+            astrophysics: self.level(124),
+            intergalactic_research_network: self.level(123),
+            graviton_technology: self.level(199),
+            armour_technology: self.level(111),
+            weapons_technology: self.level(109),
+            shielding_technology: self.level(110),
+        }
+    }
+
+    pub fn defence(&self) -> Defence {
+        Defence {
+            rocket_launcher: self.level(401),
+            light_laser: self.level(402),
+            heavy_laser: self.level(403),
+            ion_cannon: self.level(405),
+            gauss_cannon: self.level(404),
+            plasma_turret: self.level(406),
+            small_shield_dome: self.level(407),
+            large_shield_dome: self.level(408),
+            anti_ballistic_missile: self.level(502),
+            interplanetary_missile: self.level(503),
+        }
+    }
+
+    pub fn fleet(&self) -> Fleet {
+        Fleet {
+            light_fighter: self.level(204),
+            heavy_fighter: self.level(205),
+            cruiser: self.level(206),
+            battleship: self.level(207),
+            battlecruiser: self.level(215),
+            bomber: self.level(211),
+            destroyer: self.level(213),
+            deathstar: self.level(214),
+            reaper: self.level(218),
+            pathfinder: self.level(219),
+            small_cargo_ship: self.level(202),
+            large_cargo_ship: self.level(203),
+            colony_ship: self.level(208),
+            recycler: self.level(209),
+            espionage_probe: self.level(210),
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use super::script::{number_after, object_literal, string_after};
+
+use std::collections::HashMap;
+
+use anyhow::anyhow;
+use serde_json::Value;
+
+/// assignments of the token a fleet must be sent with, newer game versions use the first
+const TOKEN_ASSIGNMENTS: [&str; 2] = ["var fleetSendingToken = \"", "var token = \""];
+
+const SHIPS_DATA_ASSIGNMENT: &str = "var shipsData = ";
+
+const FLEET_COUNT_ASSIGNMENT: &str = "var fleetCount = ";
+const MAX_FLEET_COUNT_ASSIGNMENT: &str = "var maxFleetCount = ";
+
+/// what the fleet dispatch page hands to its javascript for sending a fleet
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FleetDispatch {
+    pub token: String,
+    /// cargo capacity of a single ship, including research bonuses, keyed by technology id
+    pub cargo_capacity: HashMap<u32, i64>,
+    /// deuterium a single ship burns, including class bonuses, keyed by technology id
+    pub fuel_consumption: HashMap<u32, i64>,
+    /// none if the page does not tell
+    pub slots: Option<FleetSlots>,
+}
+
+/// fleets under way and how many the account may send
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FleetSlots {
+    pub used: u32,
+    pub max: u32,
+}
+
+impl FleetSlots {
+    pub fn is_full(&self) -> bool {
+        self.used >= self.max
+    }
+}
+
+impl FleetDispatch {
+    /// cargo capacity of the ships in `units`, pairs of technology id and amount
+    pub fn capacity(&self, units: &[(u32, u32)]) -> i64 {
+        units
+            .iter()
+            .map(|(id, amount)| {
+                self.cargo_capacity.get(id).copied().unwrap_or_default() * i64::from(*amount)
+            })
+            .sum()
+    }
+
+    /// deuterium a flight of `units` over `distance` at `speed` tenths of the full speed costs,
+    /// reckoned as if every ship flew at its own top speed, which is never less than the game asks
+    pub fn consumption(&self, units: &[(u32, u32)], distance: u32, speed: u8) -> i64 {
+        let factor = f64::from(distance) / 35_000.0 * (f64::from(speed) / 10.0 + 1.0).powi(2);
+        let consumption: f64 = units
+            .iter()
+            .map(|(id, amount)| {
+                let ship = self.fuel_consumption.get(id).copied().unwrap_or_default();
+                ship as f64 * f64::from(*amount) * factor
+            })
+            .sum();
+
+        consumption.round() as i64 + 1
+    }
+}
+
+pub fn parse_fleet_dispatch(source: &str) -> anyhow::Result<FleetDispatch> {
+    let token = TOKEN_ASSIGNMENTS
+        .iter()
+        .find_map(|assignment| string_after(source, assignment))
+        .ok_or_else(|| anyhow!("fleet sending token not found"))?
+        .to_owned();
+
+    let ships = source
+        .find(SHIPS_DATA_ASSIGNMENT)
+        .ok_or_else(|| anyhow!("ships data not found"))?;
+    let ships: Value = serde_json::from_str(object_literal(
+        &source[ships + SHIPS_DATA_ASSIGNMENT.len()..],
+    )?)?;
+    // the value with bonuses, the base value if the page only has that
+    let per_ship = |key: &str, base_key: &str| {
+        ships
+            .as_object()
+            .into_iter()
+            .flatten()
+            .filter_map(|(id, ship)| {
+                let value = ship.get(key).or_else(|| ship.get(base_key))?.as_i64()?;
+                Some((id.parse::<u32>().ok()?, value))
+            })
+            .collect::<HashMap<_, _>>()
+    };
+    let cargo_capacity = per_ship("cargoCapacity", "baseCargoCapacity");
+    let fuel_consumption = per_ship("fuelConsumption", "baseFuelConsumption");
+
+    let slots = number_after(source, FLEET_COUNT_ASSIGNMENT)
+        .zip(number_after(source, MAX_FLEET_COUNT_ASSIGNMENT))
+        .map(|(used, max)| FleetSlots { used, max });
+
+    Ok(FleetDispatch {
+        token,
+        cargo_capacity,
+        fuel_consumption,
+        slots,
+    })
+}
@@ -0,0 +1,122 @@
+use super::html::{nth, Page};
+use crate::{
+    coordinates::{BodyType, Coordinates},
+    model::{FleetEvent, FleetRelation, MissionType},
+    selector::FleetEventSelectors,
+};
+
+use anyhow::anyhow;
+use chrono::{DateTime, Duration, FixedOffset, NaiveTime, TimeZone};
+
+/// how long an arrived fleet may still be listed
+const ARRIVAL_GRACE_MINUTES: i64 = 5;
+
+/// every row of the opened event list, `now` is the time on the clock of the server
+pub fn parse_fleet_events(
+    page: &Page,
+    selectors: &FleetEventSelectors,
+    now: DateTime<FixedOffset>,
+) -> anyhow::Result<Vec<FleetEvent>> {
+    let rows = format!("{}/{}", selectors.table, selectors.row);
+
+    (0..page.count(&rows)?)
+        .map(|i| {
+            Ok(FleetEvent {
+                mission_type: mission_type(page, &rows, i, selectors)?,
+                arrival_time: arrival_time(page, &rows, i, selectors, now)?,
+                coords_origin: coordinates(
+                    page,
+                    &nth(&rows, i, &selectors.coords_origin),
+                    &nth(&rows, i, &selectors.origin_type),
+                )?,
+                dest_coords: coordinates(
+                    page,
+                    &nth(&rows, i, &selectors.dest_coords),
+                    &nth(&rows, i, &selectors.dest_type),
+                )?,
+            })
+        })
+        .collect()
+}
+
+/// read from the data attributes and classes of a row, the localized title of the mission icon
+/// is only a fallback
+fn mission_type(
+    page: &Page,
+    rows: &str,
+    index: usize,
+    selectors: &FleetEventSelectors,
+) -> anyhow::Result<MissionType> {
+    let attributes = || {
+        let mission = page.number(&nth(rows, index, &selectors.mission_id))?;
+        let class = page.text(&nth(rows, index, &selectors.relation))?;
+        let relation = FleetRelation::from_class(&class)
+            .ok_or_else(|| anyhow!("unknown fleet relation {}", class))?;
+        let return_flight = page.text(&nth(rows, index, &selectors.return_flight))?;
+
+        MissionType::from_mission(
+            mission,
+            relation,
+            matches!(return_flight.as_str(), "true" | "1"),
+        )
+    };
+
+    attributes().or_else(|e| {
+        let title = page.attr(&nth(rows, index, &selectors.mission_type), "title")?;
+        MissionType::from_title(&title).map_err(|fallback| anyhow!("{}, {}", e, fallback))
+    })
+}
+
+/// read from the timestamp of a row, the clock time of the arrival cell is only a fallback and
+/// taken as its next occurrence on the clock of the server
+fn arrival_time(
+    page: &Page,
+    rows: &str,
+    index: usize,
+    selectors: &FleetEventSelectors,
+    now: DateTime<FixedOffset>,
+) -> anyhow::Result<DateTime<FixedOffset>> {
+    let timezone = *now.offset();
+    let timestamp = page
+        .text(&nth(rows, index, &selectors.arrival_timestamp))
+        .ok()
+        .and_then(|timestamp| timestamp.parse::<i64>().ok())
+        .and_then(|timestamp| timezone.timestamp_opt(timestamp, 0).single());
+    if let Some(arrival_time) = timestamp {
+        return Ok(arrival_time);
+    }
+
+    // e.g. `12:34:56 時`
+    let text = page.text(&nth(rows, index, &selectors.arrival_time))?;
+    let time = text
+        .split_whitespace()
+        .find_map(|part| NaiveTime::parse_from_str(part, "%H:%M:%S").ok())
+        .ok_or_else(|| anyhow!("invalid arrival time {}", text))?;
+    let arrival_time = timezone
+        .from_local_datetime(&now.date_naive().and_time(time))
+        .single()
+        .ok_or_else(|| anyhow!("invalid arrival time {}", text))?;
+
+    // a fleet arriving right now is still listed for a moment
+    if arrival_time < now - Duration::minutes(ARRIVAL_GRACE_MINUTES) {
+        Ok(arrival_time + Duration::days(1))
+    } else {
+        Ok(arrival_time)
+    }
+}
+
+/// coordinates of a cell, on a planet unless the icon of `body_type` tells otherwise
+pub(super) fn coordinates(
+    page: &Page,
+    xpath: &str,
+    body_type: &str,
+) -> anyhow::Result<Coordinates> {
+    let coordinates = page.text(xpath)?.parse::<Coordinates>()?;
+    let body = page
+        .texts(body_type)?
+        .first()
+        .and_then(|class| BodyType::from_class(class))
+        .unwrap_or_default();
+
+    Ok(coordinates.with_body(body))
+}
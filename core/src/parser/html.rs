@@ -0,0 +1,68 @@
+use crate::amount::Amount;
+
+use anyhow::anyhow;
+use sxd_document::Package;
+use sxd_xpath::{evaluate_xpath, Value};
+
+/// a saved or fetched game page, queried with the XPath selectors of the manifest
+pub struct Page {
+    package: Package,
+}
+
+impl Page {
+    pub fn parse(source: &str) -> Self {
+        Page {
+            package: sxd_html::parse_html(source),
+        }
+    }
+
+    /// trimmed text of every node matching `xpath`, in document order
+    pub fn texts(&self, xpath: &str) -> anyhow::Result<Vec<String>> {
+        let document = self.package.as_document();
+        let value = evaluate_xpath(&document, xpath)
+            .map_err(|e| anyhow!("invalid selector {}: {}", xpath, e))?;
+
+        match value {
+            Value::Nodeset(nodes) => Ok(nodes
+                .document_order()
+                .iter()
+                .map(|node| node.string_value().trim().to_owned())
+                .collect()),
+            _ => Err(anyhow!("selector {} does not select nodes", xpath)),
+        }
+    }
+
+    /// trimmed text of the first node matching `xpath`
+    pub fn text(&self, xpath: &str) -> anyhow::Result<String> {
+        self.texts(xpath)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("{} not found", xpath))
+    }
+
+    /// value of the attribute `name` of the first node matching `xpath`
+    pub fn attr(&self, xpath: &str, name: &str) -> anyhow::Result<String> {
+        self.text(&format!("({})[1]/@{}", xpath, name))
+    }
+
+    /// level or unit amount shown by the first node matching `xpath`
+    pub fn number(&self, xpath: &str) -> anyhow::Result<u32> {
+        number(&self.text(xpath)?)
+    }
+
+    pub fn count(&self, xpath: &str) -> anyhow::Result<usize> {
+        Ok(self.texts(xpath)?.len())
+    }
+}
+
+/// `relative` within the `index`th node of `xpath`, counting from zero
+pub fn nth(xpath: &str, index: usize, relative: &str) -> String {
+    format!("({})[{}]/{}", xpath, index + 1, relative)
+}
+
+/// a displayed level or unit amount, e.g. `30` or `1,234`
+pub fn number(text: &str) -> anyhow::Result<u32> {
+    let amount = text.parse::<Amount>()?;
+
+    u32::try_from(amount.0).map_err(|_| anyhow!("invalid number: {}", text))
+}
@@ -0,0 +1,11 @@
+//! parsers of game pages, they only read the page source and never touch the network
+
+pub mod empire;
+pub mod fleet_dispatch;
+pub mod fleet_event;
+pub mod html;
+pub mod movement;
+pub mod planet;
+pub mod planet_list;
+mod script;
+pub mod technology;
@@ -0,0 +1,63 @@
+use super::{
+    fleet_event::coordinates,
+    html::{nth, Page},
+};
+use crate::{coordinates::Coordinates, selector::MovementSelectors};
+
+use anyhow::anyhow;
+
+/// a fleet of the account under way, as listed on the fleet movement page
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FleetMovement {
+    /// `return` of the recall link, e.g. `4711`
+    pub id: String,
+    /// e.g. `crate::model::mission::EXPEDITION`
+    pub mission: u32,
+    pub return_flight: bool,
+    pub origin: Coordinates,
+    pub destination: Coordinates,
+}
+
+impl FleetMovement {
+    /// a fleet on its way back can not be recalled anymore
+    pub fn is_recallable(&self) -> bool {
+        !self.return_flight
+    }
+}
+
+/// every fleet under way, none is not an error
+pub fn parse_movement(
+    page: &Page,
+    selectors: &MovementSelectors,
+) -> anyhow::Result<Vec<FleetMovement>> {
+    (0..page.count(&selectors.fleet)?)
+        .map(|i| {
+            let id = page.text(&nth(&selectors.fleet, i, &selectors.id))?;
+            let return_flight = page
+                .texts(&nth(&selectors.fleet, i, &selectors.return_flight))?
+                .first()
+                .is_some_and(|return_flight| matches!(return_flight.as_str(), "true" | "1"));
+
+            Ok(FleetMovement {
+                // e.g. `fleet4711`
+                id: id
+                    .strip_prefix("fleet")
+                    .filter(|id| !id.is_empty() && id.bytes().all(|b| b.is_ascii_digit()))
+                    .ok_or_else(|| anyhow!("invalid fleet id {}", id))?
+                    .to_owned(),
+                mission: page.number(&nth(&selectors.fleet, i, &selectors.mission_id))?,
+                return_flight,
+                origin: coordinates(
+                    page,
+                    &nth(&selectors.fleet, i, &selectors.origin_coords),
+                    &nth(&selectors.fleet, i, &selectors.origin_type),
+                )?,
+                destination: coordinates(
+                    page,
+                    &nth(&selectors.fleet, i, &selectors.dest_coords),
+                    &nth(&selectors.fleet, i, &selectors.dest_type),
+                )?,
+            })
+        })
+        .collect()
+}
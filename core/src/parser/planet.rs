@@ -0,0 +1,144 @@
+use super::html::{number, Page};
+use crate::{
+    amount::Amount,
+    coordinates::Coordinates,
+    model::{Defence, Fleet, Infrastructure, LunarFacility, PlanetFacility, Resource},
+    selector::{
+        DefenceSelectors, FleetSelectors, InfrastructureSelectors, LunarFacilitySelectors,
+        OverviewSelectors, PlanetFacilitySelectors, ResourceSelectors,
+    },
+};
+
+use anyhow::anyhow;
+
+/// the fleet page shows this many boxes when there is no ship at all
+const EMPTY_FLEET_BOXES: usize = 6;
+
+/// location of the current planet or moon, e.g. `[1:234:5]`, the overview of a moon shows
+/// the coordinates of its planet
+pub fn parse_location(page: &Page, selectors: &OverviewSelectors) -> anyhow::Result<Coordinates> {
+    page.text(&selectors.position)?.parse()
+}
+
+/// resources of the current planet or moon, shown on every game page
+pub fn parse_resource(page: &Page, selectors: &ResourceSelectors) -> anyhow::Result<Resource> {
+    Ok(Resource {
+        metal: page.text(&selectors.metal)?.parse::<Amount>()?,
+        crystal: page.text(&selectors.crystal)?.parse::<Amount>()?,
+        deuterium: page.text(&selectors.deuterium)?.parse::<Amount>()?,
+        energy: page.text(&selectors.energy)?.parse::<Amount>()?,
+    })
+}
+
+/// levels of the supplies page
+pub fn parse_infrastructure(
+    page: &Page,
+    selectors: &InfrastructureSelectors,
+) -> anyhow::Result<Infrastructure> {
+    Ok(Infrastructure {
+        metal_mine: page.number(&selectors.metal_mine)?,
+        crystal_mine: page.number(&selectors.crystal_mine)?,
+        deuterium_synthesizer: page.number(&selectors.deuterium_synthesizer)?,
+        energy_plant: page.number(&selectors.energy_plant)?,
+        fusion_reactor: page.number(&selectors.fusion_reactor)?,
+        solar_satellite: page.number(&selectors.solar_satellite)?,
+        crawler: page.number(&selectors.crawler)?,
+        metal_storage: page.number(&selectors.metal_storage)?,
+        crystal_storage: page.number(&selectors.crystal_storage)?,
+        deuterium_tank: page.number(&selectors.deuterium_tank)?,
+    })
+}
+
+/// levels of the facilities page of a planet
+pub fn parse_planet_facility(
+    page: &Page,
+    selectors: &PlanetFacilitySelectors,
+) -> anyhow::Result<PlanetFacility> {
+    Ok(PlanetFacility {
+        robotics_factory: page.number(&selectors.robotics_factory)?,
+        shipyard: page.number(&selectors.shipyard)?,
+        research_lab: page.number(&selectors.research_lab)?,
+        alliance_depot: page.number(&selectors.alliance_depot)?,
+        missile_silo: page.number(&selectors.missile_silo)?,
+        nanite_factory: page.number(&selectors.nanite_factory)?,
+        terraformer: page.number(&selectors.terraformer)?,
+        space_dock: page.number(&selectors.space_dock)?,
+    })
+}
+
+/// levels of the facilities page of a moon
+pub fn parse_lunar_facility(
+    page: &Page,
+    selectors: &LunarFacilitySelectors,
+) -> anyhow::Result<LunarFacility> {
+    Ok(LunarFacility {
+        robotics_factory: page.number(&selectors.robotics_factory)?,
+        shipyard: page.number(&selectors.shipyard)?,
+        lunar_base: page.number(&selectors.lunar_base)?,
+        sensor_phalanx: page.number(&selectors.sensor_phalanx)?,
+        jump_gate: page.number(&selectors.jump_gate)?,
+    })
+}
+
+/// unit amounts of the defence page
+pub fn parse_defence(page: &Page, selectors: &DefenceSelectors) -> anyhow::Result<Defence> {
+    Ok(Defence {
+        rocket_launcher: page.number(&selectors.rocket_launcher)?,
+        light_laser: page.number(&selectors.light_laser)?,
+        heavy_laser: page.number(&selectors.heavy_laser)?,
+        ion_cannon: page.number(&selectors.ion_cannon)?,
+        gauss_cannon: page.number(&selectors.gauss_cannon)?,
+        plasma_turret: page.number(&selectors.plasma_turret)?,
+        small_shield_dome: page.number(&selectors.small_shield_dome)?,
+        large_shield_dome: page.number(&selectors.large_shield_dome)?,
+        anti_ballistic_missile: page.number(&selectors.anti_ballistic_missile)?,
+        interplanetary_missile: page.number(&selectors.interplanetary_missile)?,
+    })
+}
+
+/// ship amounts of the fleet dispatch page
+pub fn parse_fleet(page: &Page, selectors: &FleetSelectors) -> anyhow::Result<Fleet> {
+    if page.count(&selectors.boxes)? == EMPTY_FLEET_BOXES {
+        return Ok(Fleet::default());
+    }
+
+    let battleships = ships(page, &selectors.battleships, 10)?;
+    let civilships = ships(page, &selectors.civilships, 5)?;
+
+    Ok(Fleet {
+        light_fighter: battleships[0],
+        heavy_fighter: battleships[1],
+        cruiser: battleships[2],
+        battleship: battleships[3],
+        battlecruiser: battleships[4],
+        bomber: battleships[5],
+        destroyer: battleships[6],
+        deathstar: battleships[7],
+        reaper: battleships[8],
+        pathfinder: battleships[9],
+        small_cargo_ship: civilships[0],
+        large_cargo_ship: civilships[1],
+        colony_ship: civilships[2],
+        recycler: civilships[3],
+        espionage_probe: civilships[4],
+    })
+}
+
+/// amounts of a ship group, which lists at least `len` ships
+fn ships(page: &Page, xpath: &str, len: usize) -> anyhow::Result<Vec<u32>> {
+    let amounts = page
+        .texts(xpath)?
+        .iter()
+        .map(|text| number(text))
+        .collect::<anyhow::Result<Vec<u32>>>()?;
+    if amounts.len() < len {
+        return Err(anyhow!(
+            "{} lists {} ships, expect {}",
+            xpath,
+            amounts.len(),
+            len
+        ));
+    }
+
+    Ok(amounts)
+}
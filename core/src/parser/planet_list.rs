@@ -0,0 +1,47 @@
+use super::html::{nth, Page};
+use crate::selector::PlanetListSelectors;
+
+use anyhow::anyhow;
+
+/// a planet of the planet list with its moon
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PlanetListEntry {
+    pub planet_id: String,
+    pub lunar_id: Option<String>,
+}
+
+/// every planet of the account in the order of the sidebar
+pub fn parse_planet_list(
+    page: &Page,
+    selectors: &PlanetListSelectors,
+) -> anyhow::Result<Vec<PlanetListEntry>> {
+    let planets = page.count(&selectors.planet)?;
+    if planets == 0 {
+        return Err(anyhow!("{} not found", selectors.planet));
+    }
+
+    (0..planets)
+        .map(|i| {
+            let planet_link = page.text(&nth(&selectors.planet, i, &selectors.planet_link))?;
+            let lunar_id = page
+                .texts(&nth(&selectors.planet, i, &selectors.moon_link))?
+                .first()
+                .map(|link| body_id(link))
+                .transpose()?;
+
+            Ok(PlanetListEntry {
+                planet_id: body_id(&planet_link)?,
+                lunar_id,
+            })
+        })
+        .collect()
+}
+
+/// the `cp` parameter of a link, e.g. `...&component=overview&cp=33630196`
+fn body_id(link: &str) -> anyhow::Result<String> {
+    link.split(['?', '&'])
+        .find_map(|parameter| parameter.strip_prefix("cp="))
+        .filter(|id| !id.is_empty() && id.bytes().all(|b| b.is_ascii_digit()))
+        .map(str::to_owned)
+        .ok_or_else(|| anyhow!("no planet id in link {}", link))
+}
@@ -0,0 +1,52 @@
+use anyhow::anyhow;
+
+/// the object literal at the start of `text`, up to its matching closing brace
+pub(crate) fn object_literal(text: &str) -> anyhow::Result<&str> {
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for (i, c) in text.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        match c {
+            '"' => in_string = true,
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Ok(&text[..=i]);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Err(anyhow!("object literal is not terminated"))
+}
+
+/// the integer literal assigned right after `assignment`, e.g. `var fleetCount = `
+pub(crate) fn number_after(source: &str, assignment: &str) -> Option<u32> {
+    let start = source.find(assignment)? + assignment.len();
+    let digits = source[start..]
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(source.len() - start);
+
+    source[start..start + digits].parse().ok()
+}
+
+/// the string literal assigned right after `assignment`, e.g. `var token = "`
+pub(crate) fn string_after<'a>(source: &'a str, assignment: &str) -> Option<&'a str> {
+    let start = source.find(assignment)? + assignment.len();
+    let end = source[start..].find('"')?;
+
+    Some(&source[start..start + end])
+}
@@ -0,0 +1,27 @@
+use super::html::Page;
+use crate::{model::Technology, selector::TechnologySelectors};
+
+/// levels of the research page
+pub fn parse_technology(
+    page: &Page,
+    selectors: &TechnologySelectors,
+) -> anyhow::Result<Technology> {
+    Ok(Technology {
+        energy_technology: page.number(&selectors.energy_technology)?,
+        laser_technology: page.number(&selectors.laser_technology)?,
+        ion_technology: page.number(&selectors.ion_technology)?,
+        hyperspace_technology: page.number(&selectors.hyperspace_technology)?,
+        plasma_technology: page.number(&selectors.plasma_technology)?,
+        combustion_drive: page.number(&selectors.combustion_drive)?,
+        impulse_drive: page.number(&selectors.impulse_drive)?,
+        hyperspace_drive: page.number(&selectors.hyperspace_drive)?,
+        espionage_technology: page.number(&selectors.espionage_technology)?,
+        computer_technology: page.number(&selectors.computer_technology)?,
+        astrophysics: page.number(&selectors.astrophysics)?,
+        intergalactic_research_network: page.number(&selectors.intergalactic_research_network)?,
+        graviton_technology: page.number(&selectors.graviton_technology)?,
+        armour_technology: page.number(&selectors.armour_technology)?,
+        weapons_technology: page.number(&selectors.weapons_technology)?,
+        shielding_technology: page.number(&selectors.shielding_technology)?,
+    })
+}
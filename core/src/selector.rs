@@ -0,0 +1,353 @@
+use anyhow::{anyhow, Context};
+use serde::Deserialize;
+use toml::{value::Table, Value};
+
+/// manifest shipped with the binary
+const EMBEDDED_SELECTORS: &str = include_str!("selectors.toml");
+
+/// manifest version this build understands
+const SELECTORS_VERSION: i64 = 1;
+
+/// locators of every element the bot reads or clicks, see `selectors.toml`
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct Selectors {
+    pub version: i64,
+    pub login: LoginSelectors,
+    pub overview: OverviewSelectors,
+    pub planet_list: PlanetListSelectors,
+    pub menu: MenuSelectors,
+    pub resource: ResourceSelectors,
+    pub infrastructure: InfrastructureSelectors,
+    pub planet_facility: PlanetFacilitySelectors,
+    pub lunar_facility: LunarFacilitySelectors,
+    pub technology: TechnologySelectors,
+    pub defence: DefenceSelectors,
+    pub fleet: FleetSelectors,
+    pub fleet_event: FleetEventSelectors,
+    pub fleet_dispatch: FleetDispatchSelectors,
+    pub movement: MovementSelectors,
+}
+
+impl Selectors {
+    /// the embedded manifest with `overrides` from the config file laid over it,
+    /// fails on unknown, missing or empty selectors
+    pub fn load(overrides: Option<&Table>) -> anyhow::Result<Self> {
+        let mut manifest: Value = toml::from_str(EMBEDDED_SELECTORS)?;
+        if let Some(overrides) = overrides {
+            merge(&mut manifest, Value::Table(overrides.clone()));
+        }
+
+        check_not_empty(&manifest, "selectors")?;
+
+        let selectors: Selectors = manifest.try_into().context("invalid selectors")?;
+        if selectors.version != SELECTORS_VERSION {
+            return Err(anyhow!(
+                "unsupported selectors version {}, expect {}",
+                selectors.version,
+                SELECTORS_VERSION
+            ));
+        }
+
+        Ok(selectors)
+    }
+}
+
+/// lay `overrides` over `base`, tables are merged key by key
+fn merge(base: &mut Value, overrides: Value) {
+    match (base, overrides) {
+        (Value::Table(base), Value::Table(overrides)) => {
+            for (key, value) in overrides {
+                match base.get_mut(&key) {
+                    Some(base_value) => merge(base_value, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overrides) => *base = overrides,
+    }
+}
+
+fn check_not_empty(value: &Value, path: &str) -> anyhow::Result<()> {
+    match value {
+        Value::String(selector) if selector.trim().is_empty() => {
+            Err(anyhow!("selector {} is empty", path))
+        }
+        Value::Table(table) => table
+            .iter()
+            .try_for_each(|(key, value)| check_not_empty(value, &format!("{}.{}", path, key))),
+        _ => Ok(()),
+    }
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct LoginSelectors {
+    pub cookie_banner: String,
+    pub login_tab: String,
+    pub email: String,
+    pub password: String,
+    pub submit: String,
+    pub last_played: String,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct OverviewSelectors {
+    pub position: String,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct PlanetListSelectors {
+    pub planet: String,
+    pub planet_link: String,
+    pub moon_link: String,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct MenuSelectors {
+    pub supplies: String,
+    pub facilities: String,
+    pub research: String,
+    pub defence: String,
+    pub fleet: String,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ResourceSelectors {
+    pub metal: String,
+    pub crystal: String,
+    pub deuterium: String,
+    pub energy: String,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct InfrastructureSelectors {
+    pub metal_mine: String,
+    pub crystal_mine: String,
+    pub deuterium_synthesizer: String,
+    pub energy_plant: String,
+    pub fusion_reactor: String,
+    pub solar_satellite: String,
+    pub crawler: String,
+    pub metal_storage: String,
+    pub crystal_storage: String,
+    pub deuterium_tank: String,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct PlanetFacilitySelectors {
+    pub robotics_factory: String,
+    pub shipyard: String,
+    pub research_lab: String,
+    pub alliance_depot: String,
+    pub missile_silo: String,
+    pub nanite_factory: String,
+    pub terraformer: String,
+    pub space_dock: String,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct LunarFacilitySelectors {
+    pub robotics_factory: String,
+    pub shipyard: String,
+    pub lunar_base: String,
+    pub sensor_phalanx: String,
+    pub jump_gate: String,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct TechnologySelectors {
+    pub energy_technology: String,
+    pub laser_technology: String,
+    pub ion_technology: String,
+    pub hyperspace_technology: String,
+    pub plasma_technology: String,
+    pub combustion_drive: String,
+    pub impulse_drive: String,
+    pub hyperspace_drive: String,
+    pub espionage_technology: String,
+    pub computer_technology: String,
+    pub astrophysics: String,
+    pub intergalactic_research_network: String,
+    pub graviton_technology: String,
+    pub armour_technology: String,
+    pub weapons_technology: String,
+    pub shielding_technology: String,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct DefenceSelectors {
+    pub rocket_launcher: String,
+    pub light_laser: String,
+    pub heavy_laser: String,
+    pub ion_cannon: String,
+    pub gauss_cannon: String,
+    pub plasma_turret: String,
+    pub small_shield_dome: String,
+    pub large_shield_dome: String,
+    pub anti_ballistic_missile: String,
+    pub interplanetary_missile: String,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct FleetSelectors {
+    pub boxes: String,
+    pub battleships: String,
+    pub civilships: String,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct FleetEventSelectors {
+    pub open: String,
+    pub close: String,
+    /// shown instead of the drop down while no fleet is under way
+    pub blank: String,
+    pub table: String,
+    pub row: String,
+    pub mission_id: String,
+    pub relation: String,
+    pub return_flight: String,
+    pub mission_type: String,
+    pub arrival_time: String,
+    pub arrival_timestamp: String,
+    pub coords_origin: String,
+    pub origin_type: String,
+    pub dest_coords: String,
+    pub dest_type: String,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct FleetDispatchSelectors {
+    /// a ship amount input is found below by its `data-technology`
+    pub ships: String,
+    pub next: String,
+    pub galaxy: String,
+    pub system: String,
+    pub position: String,
+    pub planet_target: String,
+    pub moon_target: String,
+    pub debris_target: String,
+    /// a mission button is found below by its `button{id}` id
+    pub missions: String,
+    /// the n-th step is n tenths of the full speed
+    pub speed_steps: String,
+    pub metal: String,
+    pub crystal: String,
+    pub deuterium: String,
+    pub send: String,
+    /// the answer of the game to a sent fleet, its text tells why a fleet is refused
+    pub notice: String,
+    /// shown in the notice of a refused fleet
+    pub refused: String,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct MovementSelectors {
+    pub fleet: String,
+    pub id: String,
+    pub mission_id: String,
+    pub return_flight: String,
+    pub origin_coords: String,
+    pub origin_type: String,
+    pub dest_coords: String,
+    pub dest_type: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn overrides(text: &str) -> Table {
+        toml::from_str(text).unwrap()
+    }
+
+    fn load_error(text: &str) -> String {
+        format!("{:#}", Selectors::load(Some(&overrides(text))).unwrap_err())
+    }
+
+    #[test]
+    fn embedded() {
+        let selectors = Selectors::load(None).unwrap();
+
+        assert_eq!(selectors.version, SELECTORS_VERSION);
+        assert_eq!(
+            selectors.fleet_event.open,
+            "//a[@id='js_eventDetailsClosed']"
+        );
+    }
+
+    #[test]
+    fn merge_overrides() {
+        let selectors = Selectors::load(Some(&overrides(
+            r#"
+            [fleetEvent]
+            open = "//a[@id='eventToggle']"
+            "#,
+        )))
+        .unwrap();
+
+        assert_eq!(selectors.fleet_event.open, "//a[@id='eventToggle']");
+        // the rest of the section and the other sections stay embedded
+        assert_eq!(
+            selectors.fleet_event.close,
+            "//a[@id='js_eventDetailsOpen']"
+        );
+        assert_eq!(
+            selectors.overview.position,
+            "//span[@id='positionContentField']"
+        );
+    }
+
+    #[test]
+    fn reject_unknown_keys() {
+        let error = load_error(
+            r#"
+            [fleetEvent]
+            opne = "//a[@id='eventToggle']"
+            "#,
+        );
+        assert!(error.contains("unknown field `opne`"), "{}", error);
+
+        let error = load_error(
+            r#"
+            [eventList]
+            open = "//a[@id='eventToggle']"
+            "#,
+        );
+        assert!(error.contains("unknown field `eventList`"), "{}", error);
+    }
+
+    #[test]
+    fn reject_empty_selectors() {
+        let error = load_error(
+            r#"
+            [login]
+            email = " "
+            "#,
+        );
+
+        assert_eq!(error, "selector selectors.login.email is empty");
+    }
+
+    #[test]
+    fn reject_other_versions() {
+        let error = load_error("version = 2");
+
+        assert_eq!(error, "unsupported selectors version 2, expect 1");
+    }
+}
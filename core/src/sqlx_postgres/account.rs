@@ -0,0 +1,21 @@
+use sqlx::{postgres::PgPool, Row};
+
+/// id of the account in the universe, registered on first use,
+/// every other table is scoped by this id
+pub async fn ensure_account(pool: &PgPool, account: &str, universe: &str) -> sqlx::Result<i32> {
+    let row = sqlx::query(
+        r#"
+        INSERT INTO accounts (account, universe)
+        VALUES ($1, $2)
+        ON CONFLICT (account, universe) DO UPDATE SET
+            account = EXCLUDED.account
+        RETURNING id
+        "#,
+    )
+    .bind(account)
+    .bind(universe)
+    .fetch_one(pool)
+    .await?;
+
+    row.try_get("id")
+}
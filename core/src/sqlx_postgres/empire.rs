@@ -0,0 +1,542 @@
+use crate::{
+    coordinates::Coordinates,
+    model::{
+        Defence, EmpireOverview, Fleet, Infrastructure, Lunar, LunarFacility, PlanetFacility,
+        PlanetOverview, Resource, Technology,
+    },
+    sqlx_postgres::history::{append_snapshot, LUNAR_HISTORY_TABLES, PLANET_HISTORY_TABLES},
+};
+
+use chrono::{DateTime, Utc};
+use sqlx::postgres::{PgConnection, PgPool};
+
+/// upsert the latest state of every planet, its moon and the account-wide technology,
+/// then append the same state to the history tables as a snapshot at `updated_at`
+pub async fn save_empire_overview(
+    pool: &PgPool,
+    account_id: i32,
+    empire: &EmpireOverview,
+    updated_at: DateTime<Utc>,
+) -> sqlx::Result<()> {
+    let mut tx = pool.begin().await?;
+
+    for planet in empire.overview.iter() {
+        upsert_planet(&mut tx, account_id, planet, updated_at).await?;
+        // technology is shared by the whole account, the schema keeps a copy per planet
+        upsert_technology(
+            &mut tx,
+            account_id,
+            &planet.location,
+            &empire.technology,
+            updated_at,
+        )
+        .await?;
+        append_snapshot(
+            &mut tx,
+            account_id,
+            &PLANET_HISTORY_TABLES,
+            &planet.location,
+        )
+        .await?;
+
+        if let Some(lunar) = &planet.lunar {
+            upsert_lunar(&mut tx, account_id, &planet.location, lunar, updated_at).await?;
+            append_snapshot(&mut tx, account_id, &LUNAR_HISTORY_TABLES, &planet.location).await?;
+        }
+    }
+
+    tx.commit().await
+}
+
+pub async fn upsert_planet(
+    conn: &mut PgConnection,
+    account_id: i32,
+    planet: &PlanetOverview,
+    updated_at: DateTime<Utc>,
+) -> sqlx::Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO planets (updated_at, location, account_id)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (account_id, location) DO UPDATE SET
+            updated_at = EXCLUDED.updated_at
+        "#,
+    )
+    .bind(updated_at)
+    .bind(planet.location)
+    .bind(account_id)
+    .execute(&mut *conn)
+    .await?;
+
+    upsert_resource(
+        conn,
+        account_id,
+        &planet.location,
+        &planet.resource,
+        updated_at,
+    )
+    .await?;
+    upsert_infrastructure(
+        conn,
+        account_id,
+        &planet.location,
+        &planet.infrastructure,
+        updated_at,
+    )
+    .await?;
+    upsert_facility(
+        conn,
+        account_id,
+        &planet.location,
+        &planet.facility,
+        updated_at,
+    )
+    .await?;
+    upsert_defence(
+        conn,
+        account_id,
+        &planet.location,
+        &planet.defence,
+        updated_at,
+    )
+    .await?;
+    upsert_fleet(
+        conn,
+        account_id,
+        &planet.location,
+        &planet.fleet,
+        updated_at,
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// upsert a moon, keyed by the location of its parent planet
+pub async fn upsert_lunar(
+    conn: &mut PgConnection,
+    account_id: i32,
+    planet_location: &Coordinates,
+    lunar: &Lunar,
+    updated_at: DateTime<Utc>,
+) -> sqlx::Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO lunars (updated_at, location, id, account_id)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (account_id, location) DO UPDATE SET
+            updated_at = EXCLUDED.updated_at,
+            id = EXCLUDED.id
+        "#,
+    )
+    .bind(updated_at)
+    .bind(planet_location)
+    .bind(&lunar.id)
+    .bind(account_id)
+    .execute(&mut *conn)
+    .await?;
+
+    upsert_lunar_resource(
+        conn,
+        account_id,
+        planet_location,
+        &lunar.resource,
+        updated_at,
+    )
+    .await?;
+    upsert_lunar_facility(
+        conn,
+        account_id,
+        planet_location,
+        &lunar.facility,
+        updated_at,
+    )
+    .await?;
+    upsert_lunar_fleet(conn, account_id, planet_location, &lunar.fleet, updated_at).await?;
+
+    Ok(())
+}
+
+pub async fn upsert_resource(
+    conn: &mut PgConnection,
+    account_id: i32,
+    location: &Coordinates,
+    resource: &Resource,
+    updated_at: DateTime<Utc>,
+) -> sqlx::Result<()> {
+    upsert_resource_into(conn, account_id, "resource", location, resource, updated_at).await
+}
+
+pub async fn upsert_lunar_resource(
+    conn: &mut PgConnection,
+    account_id: i32,
+    location: &Coordinates,
+    resource: &Resource,
+    updated_at: DateTime<Utc>,
+) -> sqlx::Result<()> {
+    upsert_resource_into(
+        conn,
+        account_id,
+        "lunar_resource",
+        location,
+        resource,
+        updated_at,
+    )
+    .await
+}
+
+/// planets and moons store resources in tables of the same shape
+async fn upsert_resource_into(
+    conn: &mut PgConnection,
+    account_id: i32,
+    table: &str,
+    location: &Coordinates,
+    resource: &Resource,
+    updated_at: DateTime<Utc>,
+) -> sqlx::Result<()> {
+    let sql = format!(
+        r#"
+        INSERT INTO {table} (updated_at, location, metal, crystal, deuterium, energy, account_id)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        ON CONFLICT (account_id, location) DO UPDATE SET
+            updated_at = EXCLUDED.updated_at,
+            metal = EXCLUDED.metal,
+            crystal = EXCLUDED.crystal,
+            deuterium = EXCLUDED.deuterium,
+            energy = EXCLUDED.energy
+        "#
+    );
+
+    sqlx::query(&sql)
+        .bind(updated_at)
+        .bind(location)
+        .bind(resource.metal)
+        .bind(resource.crystal)
+        .bind(resource.deuterium)
+        .bind(resource.energy)
+        .bind(account_id)
+        .execute(conn)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn upsert_infrastructure(
+    conn: &mut PgConnection,
+    account_id: i32,
+    location: &Coordinates,
+    infrastructure: &Infrastructure,
+    updated_at: DateTime<Utc>,
+) -> sqlx::Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO infrastructure (
+            updated_at, location, metal_mine, crystal_mine, deuterium_synthesizer,
+            energy_plant, fusion_reactor, solar_satellite, crawler, metal_storage,
+            crystal_storage, deuterium_tank, account_id
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+        ON CONFLICT (account_id, location) DO UPDATE SET
+            updated_at = EXCLUDED.updated_at,
+            metal_mine = EXCLUDED.metal_mine,
+            crystal_mine = EXCLUDED.crystal_mine,
+            deuterium_synthesizer = EXCLUDED.deuterium_synthesizer,
+            energy_plant = EXCLUDED.energy_plant,
+            fusion_reactor = EXCLUDED.fusion_reactor,
+            solar_satellite = EXCLUDED.solar_satellite,
+            crawler = EXCLUDED.crawler,
+            metal_storage = EXCLUDED.metal_storage,
+            crystal_storage = EXCLUDED.crystal_storage,
+            deuterium_tank = EXCLUDED.deuterium_tank
+        "#,
+    )
+    .bind(updated_at)
+    .bind(location)
+    .bind(i64::from(infrastructure.metal_mine))
+    .bind(i64::from(infrastructure.crystal_mine))
+    .bind(i64::from(infrastructure.deuterium_synthesizer))
+    .bind(i64::from(infrastructure.energy_plant))
+    .bind(i64::from(infrastructure.fusion_reactor))
+    .bind(i64::from(infrastructure.solar_satellite))
+    .bind(i64::from(infrastructure.crawler))
+    .bind(i64::from(infrastructure.metal_storage))
+    .bind(i64::from(infrastructure.crystal_storage))
+    .bind(i64::from(infrastructure.deuterium_tank))
+    .bind(account_id)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn upsert_facility(
+    conn: &mut PgConnection,
+    account_id: i32,
+    location: &Coordinates,
+    facility: &PlanetFacility,
+    updated_at: DateTime<Utc>,
+) -> sqlx::Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO facility (
+            updated_at, location, robotics_factory, shipyard, research_lab, alliance_depot,
+            missile_silo, nanite_factory, terraformer, space_dock, account_id
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        ON CONFLICT (account_id, location) DO UPDATE SET
+            updated_at = EXCLUDED.updated_at,
+            robotics_factory = EXCLUDED.robotics_factory,
+            shipyard = EXCLUDED.shipyard,
+            research_lab = EXCLUDED.research_lab,
+            alliance_depot = EXCLUDED.alliance_depot,
+            missile_silo = EXCLUDED.missile_silo,
+            nanite_factory = EXCLUDED.nanite_factory,
+            terraformer = EXCLUDED.terraformer,
+            space_dock = EXCLUDED.space_dock
+        "#,
+    )
+    .bind(updated_at)
+    .bind(location)
+    .bind(i64::from(facility.robotics_factory))
+    .bind(i64::from(facility.shipyard))
+    .bind(i64::from(facility.research_lab))
+    .bind(i64::from(facility.alliance_depot))
+    .bind(i64::from(facility.missile_silo))
+    .bind(i64::from(facility.nanite_factory))
+    .bind(i64::from(facility.terraformer))
+    .bind(i64::from(facility.space_dock))
+    .bind(account_id)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn upsert_lunar_facility(
+    conn: &mut PgConnection,
+    account_id: i32,
+    location: &Coordinates,
+    facility: &LunarFacility,
+    updated_at: DateTime<Utc>,
+) -> sqlx::Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO lunar_facility (
+            updated_at, location, robotics_factory, shipyard, lunar_base, sensor_phalanx,
+            jump_gate, account_id
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        ON CONFLICT (account_id, location) DO UPDATE SET
+            updated_at = EXCLUDED.updated_at,
+            robotics_factory = EXCLUDED.robotics_factory,
+            shipyard = EXCLUDED.shipyard,
+            lunar_base = EXCLUDED.lunar_base,
+            sensor_phalanx = EXCLUDED.sensor_phalanx,
+            jump_gate = EXCLUDED.jump_gate
+        "#,
+    )
+    .bind(updated_at)
+    .bind(location)
+    .bind(i64::from(facility.robotics_factory))
+    .bind(i64::from(facility.shipyard))
+    .bind(i64::from(facility.lunar_base))
+    .bind(i64::from(facility.sensor_phalanx))
+    .bind(i64::from(facility.jump_gate))
+    .bind(account_id)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn upsert_technology(
+    conn: &mut PgConnection,
+    account_id: i32,
+    location: &Coordinates,
+    technology: &Technology,
+    updated_at: DateTime<Utc>,
+) -> sqlx::Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO technology (
+            updated_at, location, energy_technology, laser_technology, ion_technology,
+            hyperspace_technology, plasma_technology, combustion_drive, impulse_drive,
+            hyperspace_drive, espionage_technology, computer_technology, astrophysics,
+            intergalactic_research_network, graviton_technology, armour_technology,
+            weapons_technology, shielding_technology, account_id
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19)
+        ON CONFLICT (account_id, location) DO UPDATE SET
+            updated_at = EXCLUDED.updated_at,
+            energy_technology = EXCLUDED.energy_technology,
+            laser_technology = EXCLUDED.laser_technology,
+            ion_technology = EXCLUDED.ion_technology,
+            hyperspace_technology = EXCLUDED.hyperspace_technology,
+            plasma_technology = EXCLUDED.plasma_technology,
+            combustion_drive = EXCLUDED.combustion_drive,
+            impulse_drive = EXCLUDED.impulse_drive,
+            hyperspace_drive = EXCLUDED.hyperspace_drive,
+            espionage_technology = EXCLUDED.espionage_technology,
+            computer_technology = EXCLUDED.computer_technology,
+            astrophysics = EXCLUDED.astrophysics,
+            intergalactic_research_network = EXCLUDED.intergalactic_research_network,
+            graviton_technology = EXCLUDED.graviton_technology,
+            armour_technology = EXCLUDED.armour_technology,
+            weapons_technology = EXCLUDED.weapons_technology,
+            shielding_technology = EXCLUDED.shielding_technology
+        "#,
+    )
+    .bind(updated_at)
+    .bind(location)
+    .bind(i64::from(technology.energy_technology))
+    .bind(i64::from(technology.laser_technology))
+    .bind(i64::from(technology.ion_technology))
+    .bind(i64::from(technology.hyperspace_technology))
+    .bind(i64::from(technology.plasma_technology))
+    .bind(i64::from(technology.combustion_drive))
+    .bind(i64::from(technology.impulse_drive))
+    .bind(i64::from(technology.hyperspace_drive))
+    .bind(i64::from(technology.espionage_technology))
+    .bind(i64::from(technology.computer_technology))
+    .bind(i64::from(technology.astrophysics))
+    .bind(i64::from(technology.intergalactic_research_network))
+    .bind(i64::from(technology.graviton_technology))
+    .bind(i64::from(technology.armour_technology))
+    .bind(i64::from(technology.weapons_technology))
+    .bind(i64::from(technology.shielding_technology))
+    .bind(account_id)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn upsert_defence(
+    conn: &mut PgConnection,
+    account_id: i32,
+    location: &Coordinates,
+    defence: &Defence,
+    updated_at: DateTime<Utc>,
+) -> sqlx::Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO defence (
+            updated_at, location, rocket_launcher, light_laser, heavy_laser, ion_cannon,
+            gauss_cannon, plasma_turret, small_shield_dome, large_shield_dome,
+            anti_ballistic_missile, interplanetary_missile, account_id
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+        ON CONFLICT (account_id, location) DO UPDATE SET
+            updated_at = EXCLUDED.updated_at,
+            rocket_launcher = EXCLUDED.rocket_launcher,
+            light_laser = EXCLUDED.light_laser,
+            heavy_laser = EXCLUDED.heavy_laser,
+            ion_cannon = EXCLUDED.ion_cannon,
+            gauss_cannon = EXCLUDED.gauss_cannon,
+            plasma_turret = EXCLUDED.plasma_turret,
+            small_shield_dome = EXCLUDED.small_shield_dome,
+            large_shield_dome = EXCLUDED.large_shield_dome,
+            anti_ballistic_missile = EXCLUDED.anti_ballistic_missile,
+            interplanetary_missile = EXCLUDED.interplanetary_missile
+        "#,
+    )
+    .bind(updated_at)
+    .bind(location)
+    .bind(i64::from(defence.rocket_launcher))
+    .bind(i64::from(defence.light_laser))
+    .bind(i64::from(defence.heavy_laser))
+    .bind(i64::from(defence.ion_cannon))
+    .bind(i64::from(defence.gauss_cannon))
+    .bind(i64::from(defence.plasma_turret))
+    .bind(i64::from(defence.small_shield_dome))
+    .bind(i64::from(defence.large_shield_dome))
+    .bind(i64::from(defence.anti_ballistic_missile))
+    .bind(i64::from(defence.interplanetary_missile))
+    .bind(account_id)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn upsert_fleet(
+    conn: &mut PgConnection,
+    account_id: i32,
+    location: &Coordinates,
+    fleet: &Fleet,
+    updated_at: DateTime<Utc>,
+) -> sqlx::Result<()> {
+    upsert_fleet_into(conn, account_id, "fleet", location, fleet, updated_at).await
+}
+
+pub async fn upsert_lunar_fleet(
+    conn: &mut PgConnection,
+    account_id: i32,
+    location: &Coordinates,
+    fleet: &Fleet,
+    updated_at: DateTime<Utc>,
+) -> sqlx::Result<()> {
+    upsert_fleet_into(conn, account_id, "lunar_fleet", location, fleet, updated_at).await
+}
+
+/// planets and moons store fleets in tables of the same shape
+async fn upsert_fleet_into(
+    conn: &mut PgConnection,
+    account_id: i32,
+    table: &str,
+    location: &Coordinates,
+    fleet: &Fleet,
+    updated_at: DateTime<Utc>,
+) -> sqlx::Result<()> {
+    let sql = format!(
+        r#"
+        INSERT INTO {table} (
+            updated_at, location, light_fighter, heavy_fighter, cruiser, battleship,
+            battlecruiser, bomber, destroyer, deathstar, reaper, pathfinder, small_cargo_ship,
+            large_cargo_ship, colony_ship, recycler, espionage_probe, account_id
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18)
+        ON CONFLICT (account_id, location) DO UPDATE SET
+            updated_at = EXCLUDED.updated_at,
+            light_fighter = EXCLUDED.light_fighter,
+            heavy_fighter = EXCLUDED.heavy_fighter,
+            cruiser = EXCLUDED.cruiser,
+            battleship = EXCLUDED.battleship,
+            battlecruiser = EXCLUDED.battlecruiser,
+            bomber = EXCLUDED.bomber,
+            destroyer = EXCLUDED.destroyer,
+            deathstar = EXCLUDED.deathstar,
+            reaper = EXCLUDED.reaper,
+            pathfinder = EXCLUDED.pathfinder,
+            small_cargo_ship = EXCLUDED.small_cargo_ship,
+            large_cargo_ship = EXCLUDED.large_cargo_ship,
+            colony_ship = EXCLUDED.colony_ship,
+            recycler = EXCLUDED.recycler,
+            espionage_probe = EXCLUDED.espionage_probe
+        "#
+    );
+
+    sqlx::query(&sql)
+        .bind(updated_at)
+        .bind(location)
+        .bind(i64::from(fleet.light_fighter))
+        .bind(i64::from(fleet.heavy_fighter))
+        .bind(i64::from(fleet.cruiser))
+        .bind(i64::from(fleet.battleship))
+        .bind(i64::from(fleet.battlecruiser))
+        .bind(i64::from(fleet.bomber))
+        .bind(i64::from(fleet.destroyer))
+        .bind(i64::from(fleet.deathstar))
+        .bind(i64::from(fleet.reaper))
+        .bind(i64::from(fleet.pathfinder))
+        .bind(i64::from(fleet.small_cargo_ship))
+        .bind(i64::from(fleet.large_cargo_ship))
+        .bind(i64::from(fleet.colony_ship))
+        .bind(i64::from(fleet.recycler))
+        .bind(i64::from(fleet.espionage_probe))
+        .bind(account_id)
+        .execute(conn)
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,166 @@
+use crate::model::{FleetEvent, MissionType};
+
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{
+    postgres::{PgPool, PgRow},
+    Row,
+};
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FleetEventRecord {
+    pub id: i64,
+    #[serde(flatten)]
+    pub event: FleetEvent,
+    pub first_seen_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+    /// set once the event is no longer listed, e.g. the fleet arrived or was recalled
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+impl FleetEventRecord {
+    pub fn is_resolved(&self) -> bool {
+        self.resolved_at.is_some()
+    }
+}
+
+impl TryFrom<PgRow> for FleetEventRecord {
+    type Error = sqlx::Error;
+
+    fn try_from(row: PgRow) -> Result<Self, Self::Error> {
+        let mission_type: String = row.try_get("mission_type")?;
+
+        Ok(FleetEventRecord {
+            id: row.try_get("id")?,
+            event: FleetEvent {
+                mission_type: MissionType::from_str(&mission_type)
+                    .map_err(|e| sqlx::Error::Decode(Box::new(e)))?,
+                arrival_time: row.try_get("arrival_time")?,
+                coords_origin: row.try_get("coords_origin")?,
+                dest_coords: row.try_get("dest_coords")?,
+            },
+            first_seen_at: row.try_get("first_seen_at")?,
+            last_seen_at: row.try_get("last_seen_at")?,
+            resolved_at: row.try_get("resolved_at")?,
+        })
+    }
+}
+
+/// fleet events which started or stopped being listed in a refresh
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct FleetEventChanges {
+    pub new: Vec<FleetEventRecord>,
+    pub resolved: Vec<FleetEventRecord>,
+}
+
+/// record the events listed at `seen_at`, events are deduplicated by mission, origin,
+/// destination and arrival, open events missing from `events` are resolved
+pub async fn record_fleet_events(
+    pool: &PgPool,
+    account_id: i32,
+    events: &[FleetEvent],
+    seen_at: DateTime<Utc>,
+) -> sqlx::Result<FleetEventChanges> {
+    let mut tx = pool.begin().await?;
+    let mut changes = FleetEventChanges::default();
+
+    for event in events {
+        let row = sqlx::query(
+            r#"
+            INSERT INTO fleet_events (
+                account_id, mission_type, coords_origin, dest_coords, arrival_time,
+                first_seen_at, last_seen_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $6)
+            ON CONFLICT (account_id, mission_type, coords_origin, dest_coords, arrival_time)
+                WHERE resolved_at IS NULL
+            DO UPDATE SET
+                last_seen_at = EXCLUDED.last_seen_at
+            RETURNING *
+            "#,
+        )
+        .bind(account_id)
+        .bind(event.mission_type.to_string())
+        .bind(event.coords_origin)
+        .bind(event.dest_coords)
+        .bind(event.arrival_time)
+        .bind(seen_at)
+        .fetch_one(&mut tx)
+        .await?;
+
+        let record = FleetEventRecord::try_from(row)?;
+        // only a freshly inserted event was first seen in this refresh,
+        // listed twice in the same refresh is still a single new event
+        if record.first_seen_at == record.last_seen_at
+            && !changes.new.iter().any(|new| new.id == record.id)
+        {
+            changes.new.push(record);
+        }
+    }
+
+    let rows = sqlx::query(
+        r#"
+        UPDATE fleet_events SET resolved_at = $2
+        WHERE account_id = $1 AND resolved_at IS NULL AND last_seen_at < $2
+        RETURNING *
+        "#,
+    )
+    .bind(account_id)
+    .bind(seen_at)
+    .fetch_all(&mut tx)
+    .await?;
+
+    for row in rows {
+        changes.resolved.push(FleetEventRecord::try_from(row)?);
+    }
+
+    tx.commit().await?;
+
+    Ok(changes)
+}
+
+/// events still listed in the latest refresh, earliest seen first
+pub async fn open_fleet_events(
+    pool: &PgPool,
+    account_id: i32,
+) -> sqlx::Result<Vec<FleetEventRecord>> {
+    let rows = sqlx::query(
+        r#"
+        SELECT * FROM fleet_events
+        WHERE account_id = $1 AND resolved_at IS NULL
+        ORDER BY first_seen_at, id
+        "#,
+    )
+    .bind(account_id)
+    .fetch_all(pool)
+    .await?;
+
+    rows.into_iter().map(FleetEventRecord::try_from).collect()
+}
+
+/// every event first seen between `from` and `to`, earliest seen first
+pub async fn fleet_events_between(
+    pool: &PgPool,
+    account_id: i32,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> sqlx::Result<Vec<FleetEventRecord>> {
+    let rows = sqlx::query(
+        r#"
+        SELECT * FROM fleet_events
+        WHERE account_id = $1 AND first_seen_at BETWEEN $2 AND $3
+        ORDER BY first_seen_at, id
+        "#,
+    )
+    .bind(account_id)
+    .bind(from)
+    .bind(to)
+    .fetch_all(pool)
+    .await?;
+
+    rows.into_iter().map(FleetEventRecord::try_from).collect()
+}
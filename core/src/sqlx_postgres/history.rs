@@ -0,0 +1,295 @@
+use crate::{
+    coordinates::{BodyType, Coordinates},
+    model::Resource,
+};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{
+    postgres::{PgConnection, PgPool},
+    Row,
+};
+
+/// a current state table with an append-only `*_history` twin, `updated_at` becomes `snapshot_at`
+pub(crate) struct HistoryTable {
+    pub name: &'static str,
+    /// copied besides the account, location and time
+    pub columns: &'static [&'static str],
+}
+
+const RESOURCE_COLUMNS: &[&str] = &["metal", "crystal", "deuterium", "energy"];
+
+const FLEET_COLUMNS: &[&str] = &[
+    "light_fighter",
+    "heavy_fighter",
+    "cruiser",
+    "battleship",
+    "battlecruiser",
+    "bomber",
+    "destroyer",
+    "deathstar",
+    "reaper",
+    "pathfinder",
+    "small_cargo_ship",
+    "large_cargo_ship",
+    "colony_ship",
+    "recycler",
+    "espionage_probe",
+];
+
+/// current state tables of a planet which have an append-only `*_history` twin
+pub(crate) const PLANET_HISTORY_TABLES: [HistoryTable; 6] = [
+    HistoryTable {
+        name: "resource",
+        columns: RESOURCE_COLUMNS,
+    },
+    HistoryTable {
+        name: "infrastructure",
+        columns: &[
+            "metal_mine",
+            "crystal_mine",
+            "deuterium_synthesizer",
+            "energy_plant",
+            "fusion_reactor",
+            "solar_satellite",
+            "crawler",
+            "metal_storage",
+            "crystal_storage",
+            "deuterium_tank",
+        ],
+    },
+    HistoryTable {
+        name: "facility",
+        columns: &[
+            "robotics_factory",
+            "shipyard",
+            "research_lab",
+            "alliance_depot",
+            "missile_silo",
+            "nanite_factory",
+            "terraformer",
+            "space_dock",
+        ],
+    },
+    HistoryTable {
+        name: "technology",
+        columns: &[
+            "energy_technology",
+            "laser_technology",
+            "ion_technology",
+            "hyperspace_technology",
+            "plasma_technology",
+            "combustion_drive",
+            "impulse_drive",
+            "hyperspace_drive",
+            "espionage_technology",
+            "computer_technology",
+            "astrophysics",
+            "intergalactic_research_network",
+            "graviton_technology",
+            "armour_technology",
+            "weapons_technology",
+            "shielding_technology",
+        ],
+    },
+    HistoryTable {
+        name: "defence",
+        columns: &[
+            "rocket_launcher",
+            "light_laser",
+            "heavy_laser",
+            "ion_cannon",
+            "gauss_cannon",
+            "plasma_turret",
+            "small_shield_dome",
+            "large_shield_dome",
+            "anti_ballistic_missile",
+            "interplanetary_missile",
+        ],
+    },
+    HistoryTable {
+        name: "fleet",
+        columns: FLEET_COLUMNS,
+    },
+];
+
+/// current state tables of a moon which have an append-only `*_history` twin
+pub(crate) const LUNAR_HISTORY_TABLES: [HistoryTable; 3] = [
+    HistoryTable {
+        name: "lunar_resource",
+        columns: RESOURCE_COLUMNS,
+    },
+    HistoryTable {
+        name: "lunar_facility",
+        columns: &[
+            "robotics_factory",
+            "shipyard",
+            "lunar_base",
+            "sensor_phalanx",
+            "jump_gate",
+        ],
+    },
+    HistoryTable {
+        name: "lunar_fleet",
+        columns: FLEET_COLUMNS,
+    },
+];
+
+/// history tables holding building and research levels
+const LEVEL_TABLES: [&str; 4] = ["infrastructure", "facility", "lunar_facility", "technology"];
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceSnapshot {
+    pub snapshot_at: DateTime<Utc>,
+    pub location: Coordinates,
+    pub resource: Resource,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LevelChange {
+    pub changed_at: DateTime<Utc>,
+    pub location: Coordinates,
+    /// table the level belongs to, e.g. `infrastructure`
+    pub category: String,
+    /// column name of the building or research, e.g. `metal_mine`
+    pub name: String,
+    pub previous_level: i64,
+    pub level: i64,
+}
+
+/// copy the current state of a planet or moon into the history tables,
+/// must run after the current state tables are upserted
+pub(crate) async fn append_snapshot(
+    conn: &mut PgConnection,
+    account_id: i32,
+    tables: &[HistoryTable],
+    location: &Coordinates,
+) -> sqlx::Result<()> {
+    for table in tables {
+        let columns = table.columns.join(", ");
+        let sql = format!(
+            r#"
+            INSERT INTO {table}_history (snapshot_at, location, account_id, {columns})
+            SELECT updated_at, location, account_id, {columns} FROM {table}
+            WHERE account_id = $1 AND location = $2
+            ON CONFLICT DO NOTHING
+            "#,
+            table = table.name,
+        );
+        sqlx::query(&sql)
+            .bind(account_id)
+            .bind(location)
+            .execute(&mut *conn)
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// resources of a planet snapshotted between `from` and `to`, oldest first
+pub async fn resource_between(
+    pool: &PgPool,
+    account_id: i32,
+    location: &Coordinates,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> sqlx::Result<Vec<ResourceSnapshot>> {
+    let rows = sqlx::query(
+        r#"
+        SELECT snapshot_at, location, metal, crystal, deuterium, energy
+        FROM resource_history
+        WHERE account_id = $1 AND location = $2 AND snapshot_at BETWEEN $3 AND $4
+        ORDER BY snapshot_at
+        "#,
+    )
+    .bind(account_id)
+    .bind(location)
+    .bind(from)
+    .bind(to)
+    .fetch_all(pool)
+    .await?;
+
+    rows.into_iter()
+        .map(|row| {
+            Ok(ResourceSnapshot {
+                snapshot_at: row.try_get("snapshot_at")?,
+                location: row.try_get("location")?,
+                resource: Resource {
+                    metal: row.try_get("metal")?,
+                    crystal: row.try_get("crystal")?,
+                    deuterium: row.try_get("deuterium")?,
+                    energy: row.try_get("energy")?,
+                },
+            })
+        })
+        .collect()
+}
+
+/// every building and research level that changed since `since`,
+/// optionally limited to a single planet or moon, oldest first
+pub async fn level_changes_since(
+    pool: &PgPool,
+    account_id: i32,
+    location: Option<&Coordinates>,
+    since: DateTime<Utc>,
+) -> sqlx::Result<Vec<LevelChange>> {
+    let mut changes = Vec::new();
+
+    for table in LEVEL_TABLES {
+        // lunar tables are keyed by the location of their planet
+        let lunar = table.starts_with("lunar_");
+        if location.is_some_and(|location| (location.body == BodyType::Moon) != lunar) {
+            continue;
+        }
+        let filter = location.map(|location| location.planet());
+
+        // unpivot every level column, then compare each snapshot with the one before it,
+        // so the first change after `since` is still detected against an older snapshot
+        let sql = format!(
+            r#"
+            WITH levels AS (
+                SELECT h.snapshot_at, h.location, level.key AS name,
+                    level.value::NUMERIC::BIGINT AS level
+                FROM {table}_history h
+                CROSS JOIN LATERAL jsonb_each_text(
+                    to_jsonb(h) - 'snapshot_at' - 'location' - 'account_id'
+                ) AS level
+                WHERE h.account_id = $1 AND ($2::VARCHAR IS NULL OR h.location = $2)
+            ),
+            changes AS (
+                SELECT snapshot_at, location, name, level,
+                    LAG(level) OVER (PARTITION BY location, name ORDER BY snapshot_at) AS previous_level
+                FROM levels
+            )
+            SELECT snapshot_at, location, name, previous_level, level
+            FROM changes
+            WHERE snapshot_at >= $3 AND previous_level IS NOT NULL AND previous_level <> level
+            "#
+        );
+
+        let rows = sqlx::query(&sql)
+            .bind(account_id)
+            .bind(filter)
+            .bind(since)
+            .fetch_all(pool)
+            .await?;
+
+        for row in rows {
+            let location: Coordinates = row.try_get("location")?;
+            changes.push(LevelChange {
+                changed_at: row.try_get("snapshot_at")?,
+                location: if lunar { location.moon() } else { location },
+                category: table.to_owned(),
+                name: row.try_get("name")?,
+                previous_level: row.try_get("previous_level")?,
+                level: row.try_get("level")?,
+            });
+        }
+    }
+
+    changes.sort_by_key(|change| change.changed_at);
+
+    Ok(changes)
+}
@@ -1,14 +1,12 @@
-use serde::Serialize;
-use sqlx::types::Json;
+pub mod account;
+pub mod empire;
+pub mod fleet_event;
+pub mod history;
+pub mod saved_fleet;
+
 use sqlx::{
     migrate::Migrator,
-    postgres::{PgConnection, PgPool, PgPoolOptions, PgQueryResult, PgRow, Postgres},
-    Error as SqlxError, Executor, Row,
-};
-use std::{
-    fmt::Debug,
-    hash::{Hash, Hasher},
-    str::FromStr,
+    postgres::{PgPool, PgPoolOptions},
 };
 
 // re-export
@@ -0,0 +1,148 @@
+use crate::{
+    coordinates::Coordinates,
+    model::{FleetEvent, MissionType},
+};
+
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{
+    postgres::{PgPool, PgRow},
+    Row,
+};
+
+/// a fleet sent away from an attack, recalled once the attack is over
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedFleet {
+    /// id of the planet or moon the fleet left
+    pub planet_id: String,
+    pub location: Coordinates,
+    pub destination: Coordinates,
+    /// e.g. `crate::model::mission::EXPEDITION`
+    pub mission: u32,
+    pub attack: FleetEvent,
+    pub saved_at: DateTime<Utc>,
+    /// set once the attack is no longer listed
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedFleetRecord {
+    pub id: i64,
+    #[serde(flatten)]
+    pub fleet: SavedFleet,
+}
+
+impl TryFrom<PgRow> for SavedFleetRecord {
+    type Error = sqlx::Error;
+
+    fn try_from(row: PgRow) -> Result<Self, Self::Error> {
+        let mission_type: String = row.try_get("attack_mission_type")?;
+        let mission: i32 = row.try_get("mission")?;
+
+        Ok(SavedFleetRecord {
+            id: row.try_get("id")?,
+            fleet: SavedFleet {
+                planet_id: row.try_get("planet_id")?,
+                location: row.try_get("location")?,
+                destination: row.try_get("destination")?,
+                mission: u32::try_from(mission).map_err(|e| sqlx::Error::Decode(Box::new(e)))?,
+                attack: FleetEvent {
+                    mission_type: MissionType::from_str(&mission_type)
+                        .map_err(|e| sqlx::Error::Decode(Box::new(e)))?,
+                    arrival_time: row.try_get("attack_arrival_time")?,
+                    coords_origin: row.try_get("attack_coords_origin")?,
+                    dest_coords: row.try_get("attack_dest_coords")?,
+                },
+                saved_at: row.try_get("saved_at")?,
+                resolved_at: row.try_get("resolved_at")?,
+            },
+        })
+    }
+}
+
+/// start tracking a saved fleet, its id is returned
+pub async fn insert_saved_fleet(
+    pool: &PgPool,
+    account_id: i32,
+    fleet: &SavedFleet,
+) -> sqlx::Result<i64> {
+    let row = sqlx::query(
+        r#"
+        INSERT INTO saved_fleets (
+            account_id, planet_id, location, destination, mission, attack_mission_type,
+            attack_coords_origin, attack_dest_coords, attack_arrival_time, saved_at, resolved_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        RETURNING id
+        "#,
+    )
+    .bind(account_id)
+    .bind(&fleet.planet_id)
+    .bind(fleet.location)
+    .bind(fleet.destination)
+    .bind(fleet.mission as i32)
+    .bind(fleet.attack.mission_type.to_string())
+    .bind(fleet.attack.coords_origin)
+    .bind(fleet.attack.dest_coords)
+    .bind(fleet.attack.arrival_time)
+    .bind(fleet.saved_at)
+    .bind(fleet.resolved_at)
+    .fetch_one(pool)
+    .await?;
+
+    row.try_get("id")
+}
+
+pub async fn resolve_saved_fleet(
+    pool: &PgPool,
+    id: i64,
+    resolved_at: DateTime<Utc>,
+) -> sqlx::Result<()> {
+    sqlx::query("UPDATE saved_fleets SET resolved_at = $2 WHERE id = $1")
+        .bind(id)
+        .bind(resolved_at)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// stop tracking a saved fleet, `recalled` is false if it was no longer under way
+pub async fn close_saved_fleet(
+    pool: &PgPool,
+    id: i64,
+    closed_at: DateTime<Utc>,
+    recalled: bool,
+) -> sqlx::Result<()> {
+    sqlx::query("UPDATE saved_fleets SET closed_at = $2, recalled = $3 WHERE id = $1")
+        .bind(id)
+        .bind(closed_at)
+        .bind(recalled)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// fleets still tracked, earliest saved first
+pub async fn open_saved_fleets(
+    pool: &PgPool,
+    account_id: i32,
+) -> sqlx::Result<Vec<SavedFleetRecord>> {
+    let rows = sqlx::query(
+        r#"
+        SELECT * FROM saved_fleets
+        WHERE account_id = $1 AND closed_at IS NULL
+        ORDER BY saved_at, id
+        "#,
+    )
+    .bind(account_id)
+    .fetch_all(pool)
+    .await?;
+
+    rows.into_iter().map(SavedFleetRecord::try_from).collect()
+}
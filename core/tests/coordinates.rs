@@ -0,0 +1,59 @@
+use ogc_core::coordinates::{BodyType, Coordinates};
+
+#[test]
+fn parse_and_display() {
+    let planet = Coordinates::new(1, 234, 5, BodyType::Planet);
+
+    assert_eq!("[1:234:5]".parse::<Coordinates>().unwrap(), planet);
+    assert_eq!(" 1:234:5 ".parse::<Coordinates>().unwrap(), planet);
+    assert_eq!(
+        "[1:234:5] moon".parse::<Coordinates>().unwrap(),
+        planet.moon()
+    );
+    assert_eq!(planet.to_string(), "[1:234:5]");
+    assert_eq!(planet.moon().to_string(), "[1:234:5] moon");
+    assert_eq!(
+        planet.with_body(BodyType::Debris).to_string(),
+        "[1:234:5] debris"
+    );
+
+    for invalid in ["", "[1:234]", "[1:234:5:6]", "[1:x:5]", "[1:234:5] star"] {
+        assert!(invalid.parse::<Coordinates>().is_err(), "{}", invalid);
+    }
+}
+
+#[test]
+fn planet_and_moon_differ() {
+    let planet = Coordinates::new(1, 234, 5, BodyType::Planet);
+
+    assert_ne!(planet, planet.moon());
+    assert!(planet.same_position(&planet.moon()));
+    assert!(!planet.same_position(&Coordinates::new(1, 234, 6, BodyType::Planet)));
+}
+
+#[test]
+fn distance() {
+    let planet = Coordinates::new(1, 234, 5, BodyType::Planet);
+    let cases = [
+        (planet.moon(), 5),
+        (planet.with_body(BodyType::Debris), 5),
+        (Coordinates::new(1, 234, 16, BodyType::Planet), 1_055),
+        (Coordinates::new(1, 230, 16, BodyType::Moon), 3_080),
+        (Coordinates::new(3, 234, 5, BodyType::Planet), 40_000),
+    ];
+    for (other, expected) in cases {
+        assert_eq!(planet.distance(&other), expected, "{}", other);
+        assert_eq!(other.distance(&planet), expected, "{}", other);
+    }
+}
+
+#[test]
+fn serde_as_text() {
+    let moon = Coordinates::new(2, 17, 4, BodyType::Moon);
+
+    assert_eq!(serde_json::to_string(&moon).unwrap(), r#""[2:17:4] moon""#);
+    assert_eq!(
+        serde_json::from_str::<Coordinates>(r#""[2:17:4] moon""#).unwrap(),
+        moon
+    );
+}
@@ -0,0 +1,460 @@
+use std::{fs, path::PathBuf};
+
+use chrono::{DateTime, FixedOffset};
+use ogc_core::{
+    amount::Amount,
+    coordinates::Coordinates,
+    model::{
+        Defence, Fleet, FleetEvent, Infrastructure, LunarFacility, MissionType, PlanetFacility,
+        Resource, Technology,
+    },
+    parser::{
+        empire::parse_empire,
+        fleet_dispatch::{parse_fleet_dispatch, FleetSlots},
+        fleet_event::parse_fleet_events,
+        html::Page,
+        movement::{parse_movement, FleetMovement},
+        planet::{
+            parse_defence, parse_fleet, parse_infrastructure, parse_location, parse_lunar_facility,
+            parse_planet_facility, parse_resource,
+        },
+        planet_list::{parse_planet_list, PlanetListEntry},
+        technology::parse_technology,
+    },
+    selector::Selectors,
+};
+
+fn coordinates(text: &str) -> Coordinates {
+    text.parse().unwrap()
+}
+
+/// a time on the clock of a tw server
+fn server_time(text: &str) -> DateTime<FixedOffset> {
+    DateTime::parse_from_rfc3339(text).unwrap()
+}
+
+fn source(name: &str) -> String {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name);
+
+    fs::read_to_string(&path).unwrap_or_else(|e| panic!("read fixture {}: {}", path.display(), e))
+}
+
+fn fixture(name: &str) -> Page {
+    Page::parse(&source(name))
+}
+
+fn selectors() -> Selectors {
+    Selectors::load(None).expect("embedded selectors")
+}
+
+#[test]
+fn location_and_resource() {
+    let page = fixture("overview.html");
+    let selectors = selectors();
+
+    assert_eq!(
+        parse_location(&page, &selectors.overview).unwrap(),
+        coordinates("[1:234:5]")
+    );
+    assert_eq!(
+        parse_resource(&page, &selectors.resource).unwrap(),
+        Resource {
+            metal: Amount(4_567_890),
+            crystal: Amount(2_345_678),
+            deuterium: Amount(876_543),
+            energy: Amount(-120),
+        }
+    );
+}
+
+#[test]
+fn planet_list() {
+    let page = fixture("overview.html");
+    let planet = |planet_id: &str, lunar_id: Option<&str>| PlanetListEntry {
+        planet_id: planet_id.to_owned(),
+        lunar_id: lunar_id.map(str::to_owned),
+    };
+
+    assert_eq!(
+        parse_planet_list(&page, &selectors().planet_list).unwrap(),
+        vec![
+            planet("33630196", Some("33631729")),
+            planet("33630280", None),
+            planet("33630744", Some("33631621")),
+        ]
+    );
+    assert!(parse_planet_list(&fixture("empire.html"), &selectors().planet_list).is_err());
+}
+
+#[test]
+fn infrastructure() {
+    let page = fixture("supplies.html");
+
+    assert_eq!(
+        parse_infrastructure(&page, &selectors().infrastructure).unwrap(),
+        Infrastructure {
+            metal_mine: 30,
+            crystal_mine: 26,
+            deuterium_synthesizer: 24,
+            energy_plant: 28,
+            fusion_reactor: 15,
+            solar_satellite: 1250,
+            crawler: 120,
+            metal_storage: 11,
+            crystal_storage: 10,
+            deuterium_tank: 9,
+        }
+    );
+}
+
+#[test]
+fn planet_facility() {
+    let page = fixture("facilities.html");
+
+    assert_eq!(
+        parse_planet_facility(&page, &selectors().planet_facility).unwrap(),
+        PlanetFacility {
+            robotics_factory: 10,
+            shipyard: 12,
+            research_lab: 12,
+            alliance_depot: 2,
+            missile_silo: 4,
+            nanite_factory: 5,
+            terraformer: 3,
+            space_dock: 6,
+        }
+    );
+}
+
+#[test]
+fn lunar_facility() {
+    let page = fixture("moon_facilities.html");
+
+    assert_eq!(
+        parse_lunar_facility(&page, &selectors().lunar_facility).unwrap(),
+        LunarFacility {
+            robotics_factory: 4,
+            shipyard: 2,
+            lunar_base: 7,
+            sensor_phalanx: 5,
+            jump_gate: 1,
+        }
+    );
+}
+
+#[test]
+fn technology() {
+    let page = fixture("research.html");
+
+    assert_eq!(
+        parse_technology(&page, &selectors().technology).unwrap(),
+        Technology {
+            energy_technology: 16,
+            laser_technology: 12,
+            ion_technology: 5,
+            hyperspace_technology: 8,
+            plasma_technology: 14,
+            combustion_drive: 18,
+            impulse_drive: 15,
+            hyperspace_drive: 13,
+            espionage_technology: 14,
+            computer_technology: 15,
+            astrophysics: 17,
+            intergalactic_research_network: 6,
+            graviton_technology: 1,
+            armour_technology: 16,
+            weapons_technology: 15,
+            shielding_technology: 14,
+        }
+    );
+}
+
+#[test]
+fn defence() {
+    let page = fixture("defenses.html");
+
+    assert_eq!(
+        parse_defence(&page, &selectors().defence).unwrap(),
+        Defence {
+            rocket_launcher: 2500,
+            light_laser: 1800,
+            heavy_laser: 300,
+            ion_cannon: 80,
+            gauss_cannon: 120,
+            plasma_turret: 20,
+            small_shield_dome: 1,
+            large_shield_dome: 1,
+            anti_ballistic_missile: 20,
+            interplanetary_missile: 0,
+        }
+    );
+}
+
+#[test]
+fn fleet() {
+    let page = fixture("fleetdispatch.html");
+
+    assert_eq!(
+        parse_fleet(&page, &selectors().fleet).unwrap(),
+        Fleet {
+            light_fighter: 1200,
+            heavy_fighter: 0,
+            cruiser: 150,
+            battleship: 300,
+            battlecruiser: 200,
+            bomber: 25,
+            destroyer: 40,
+            deathstar: 1,
+            reaper: 60,
+            pathfinder: 80,
+            small_cargo_ship: 50,
+            large_cargo_ship: 1500,
+            colony_ship: 0,
+            recycler: 400,
+            espionage_probe: 60,
+        }
+    );
+}
+
+#[test]
+fn empty_fleet() {
+    let page = fixture("fleetdispatch_empty.html");
+
+    assert_eq!(
+        parse_fleet(&page, &selectors().fleet).unwrap(),
+        Fleet::default()
+    );
+}
+
+#[test]
+fn fleet_events() {
+    let page = fixture("overview.html");
+
+    assert_eq!(
+        parse_fleet_events(
+            &page,
+            &selectors().fleet_event,
+            server_time("2022-10-17T12:00:00+08:00")
+        )
+        .unwrap(),
+        vec![
+            FleetEvent {
+                mission_type: MissionType::EnemyAttacking,
+                arrival_time: server_time("2022-10-17T12:34:56+08:00"),
+                coords_origin: coordinates("[2:101:8]"),
+                dest_coords: coordinates("[1:234:5]"),
+            },
+            FleetEvent {
+                mission_type: MissionType::Transport,
+                arrival_time: server_time("2022-10-17T13:05:00+08:00"),
+                coords_origin: coordinates("[1:234:5]"),
+                dest_coords: coordinates("[1:234:7] moon"),
+            },
+            FleetEvent {
+                mission_type: MissionType::ExpeditionReturn,
+                arrival_time: server_time("2022-10-17T18:20:11+08:00"),
+                coords_origin: coordinates("[1:234:16]"),
+                dest_coords: coordinates("[1:234:5]"),
+            },
+        ]
+    );
+}
+
+/// a row as the game renders it for other languages, `mission` is the title of the icon
+fn event_row(attributes: &str, class: &str, mission: &str) -> String {
+    format!(
+        r#"<tr class="eventFleet" {attributes}>
+    <td class="countDown"><span class="{class} textBeefy">5m</span></td>
+    <td class="arrivalTime">10:00:00</td>
+    <td class="missionFleet"><img src="" title="{mission}"></td>
+    <td class="coordsOrigin">[2:1:1]</td>
+    <td class="destCoords">[1:1:1]</td>
+</tr>"#
+    )
+}
+
+fn event_table(rows: &[String]) -> Page {
+    Page::parse(&format!(
+        r#"<html><body><table id="eventContent"><tbody>{}</tbody></table></body></html>"#,
+        rows.concat()
+    ))
+}
+
+fn mission_types(rows: &[String]) -> Vec<MissionType> {
+    parse_fleet_events(
+        &event_table(rows),
+        &selectors().fleet_event,
+        server_time("2022-10-17T09:00:00+08:00"),
+    )
+    .unwrap()
+    .into_iter()
+    .map(|event| event.mission_type)
+    .collect()
+}
+
+#[test]
+fn mission_type_from_attributes() {
+    let attributes = |mission: u32, return_flight: bool| {
+        format!(
+            r#"data-mission-type="{}" data-return-flight="{}""#,
+            mission, return_flight
+        )
+    };
+
+    assert_eq!(
+        mission_types(&[
+            event_row(
+                &attributes(1, false),
+                "hostile",
+                "Flotte ennemie | Attaquer"
+            ),
+            event_row(&attributes(6, false), "hostile", ""),
+            event_row(&attributes(3, false), "neutral", ""),
+            event_row(&attributes(4, true), "friendly", ""),
+            event_row(&attributes(18, false), "friendly", ""),
+        ]),
+        vec![
+            MissionType::EnemyAttacking,
+            MissionType::EnemyEspionage,
+            MissionType::FriendlyTransport,
+            MissionType::DeploymentReturn,
+            MissionType::SearchingForLifeforms,
+        ]
+    );
+}
+
+#[test]
+fn mission_type_from_title() {
+    assert_eq!(
+        mission_types(&[
+            event_row("", "", "Hostile fleet | Attack"),
+            event_row("", "", "Eigene Flotte | Expedition (R)"),
+            event_row("", "", "己方艦隊 | 採集回收 (返)"),
+            event_row("", "", "Friendly fleet | ACS Defend"),
+        ]),
+        vec![
+            MissionType::EnemyAttacking,
+            MissionType::ExpeditionReturn,
+            MissionType::HarvestingReturn,
+            MissionType::FriendlyACSDefend,
+        ]
+    );
+
+    let page = event_table(&[event_row("", "", "Flotte ennemie | Attaquer")]);
+    assert!(parse_fleet_events(
+        &page,
+        &selectors().fleet_event,
+        server_time("2022-10-17T09:00:00+08:00")
+    )
+    .is_err());
+}
+
+#[test]
+fn arrival_time_from_clock() {
+    // the rows arrive at 10:00:00 without a timestamp
+    let page = event_table(&[event_row("", "", "Hostile fleet | Attack")]);
+    let arrival_time = |now: &str| {
+        parse_fleet_events(&page, &selectors().fleet_event, server_time(now)).unwrap()[0]
+            .arrival_time
+    };
+
+    assert_eq!(
+        arrival_time("2022-10-17T09:00:00+08:00"),
+        server_time("2022-10-17T10:00:00+08:00")
+    );
+    // just arrived
+    assert_eq!(
+        arrival_time("2022-10-17T10:01:00+08:00"),
+        server_time("2022-10-17T10:00:00+08:00")
+    );
+    assert_eq!(
+        arrival_time("2022-10-17T23:00:00+08:00"),
+        server_time("2022-10-18T10:00:00+08:00")
+    );
+}
+
+#[test]
+fn movement() {
+    let page = fixture("movement.html");
+
+    assert_eq!(
+        parse_movement(&page, &selectors().movement).unwrap(),
+        vec![
+            FleetMovement {
+                id: "4711".to_owned(),
+                mission: 15,
+                return_flight: false,
+                origin: coordinates("[1:234:5]"),
+                destination: coordinates("[1:234:16]"),
+            },
+            FleetMovement {
+                id: "4712".to_owned(),
+                mission: 4,
+                return_flight: true,
+                origin: coordinates("[1:234:5] moon"),
+                destination: coordinates("[1:240:9]"),
+            },
+        ]
+    );
+    assert!(
+        parse_movement(&fixture("overview.html"), &selectors().movement)
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn missing_element() {
+    let page = fixture("supplies.html");
+
+    assert!(parse_technology(&page, &selectors().technology).is_err());
+}
+
+#[test]
+fn empire() {
+    let entries = parse_empire(&source("empire.html")).unwrap();
+
+    assert_eq!(entries.len(), 2);
+    let home = &entries[0];
+    assert_eq!(home.id, "33630196");
+    assert_eq!(home.location, coordinates("[1:234:5]"));
+    assert_eq!(home.resource.metal, Amount(4_567_890));
+    assert_eq!(home.resource.energy, Amount(-120));
+    assert_eq!(home.infrastructure().metal_mine, 30);
+    assert_eq!(home.planet_facility().space_dock, 6);
+    assert_eq!(home.technology().armour_technology, 16);
+    assert_eq!(home.defence().gauss_cannon, 120);
+    assert_eq!(home.fleet().light_fighter, 1200);
+    assert_eq!(entries[1].location, coordinates("[1:240:8]"));
+    assert_eq!(entries[1].fleet(), Fleet::default());
+}
+
+#[test]
+fn fleet_dispatch() {
+    let dispatch = parse_fleet_dispatch(&source("fleetdispatch.html")).unwrap();
+
+    assert_eq!(dispatch.token, "3f6f2e1c8a0b4d5e9f7a6b5c4d3e2f10");
+    assert_eq!(dispatch.cargo_capacity.len(), 15);
+    assert_eq!(dispatch.capacity(&[(204, 10), (203, 2), (210, 5)]), 63_120);
+    // only the light fighters burn deuterium on the saved page
+    let cases = [
+        (5, 10, 1),
+        (1_055, 1, 4),
+        (1_055, 10, 11),
+        (2_795, 5, 15),
+        (20_000, 10, 184),
+    ];
+    for (distance, speed, expected) in cases {
+        assert_eq!(
+            dispatch.consumption(&[(204, 10), (203, 2)], distance, speed),
+            expected,
+            "{} at {}",
+            distance,
+            speed
+        );
+    }
+    assert_eq!(dispatch.consumption(&[], 20_000, 10), 1);
+    assert_eq!(dispatch.slots, Some(FleetSlots { used: 3, max: 11 }));
+}
@@ -0,0 +1,292 @@
+// every test gets a database of its own, created from `DATABASE_URL` and migrated
+#![cfg(database_tests)]
+
+use std::{borrow::Cow, fs, path::PathBuf, slice};
+
+use chrono::{DateTime, Duration, FixedOffset, TimeZone, Utc};
+use ogc_core::{
+    coordinates::Coordinates,
+    model::{EmpireOverview, FleetEvent, Lunar, MissionType, PlanetOverview},
+    parser::empire::parse_empire,
+    sqlx_postgres::{
+        account::ensure_account,
+        empire::save_empire_overview,
+        fleet_event::{open_fleet_events, record_fleet_events},
+        history::level_changes_since,
+        saved_fleet::{
+            close_saved_fleet, insert_saved_fleet, open_saved_fleets, resolve_saved_fleet,
+            SavedFleet,
+        },
+    },
+};
+use sqlx::{migrate::Migrator, PgPool, Row};
+
+fn coordinates(text: &str) -> Coordinates {
+    text.parse().unwrap()
+}
+
+fn at(seconds: i64) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2023, 3, 1, 12, 0, 0).unwrap() + Duration::seconds(seconds)
+}
+
+/// the planets of the saved empire page, the first one with a moon
+fn empire() -> EmpireOverview {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/empire.html");
+    let entries = parse_empire(&fs::read_to_string(path).unwrap()).unwrap();
+    let technology = entries[0].technology();
+    let overview = entries
+        .iter()
+        .enumerate()
+        .map(|(index, entry)| PlanetOverview {
+            id: entry.id.clone(),
+            location: entry.location,
+            resource: entry.resource.clone(),
+            infrastructure: entry.infrastructure(),
+            facility: entry.planet_facility(),
+            defence: entry.defence(),
+            fleet: entry.fleet(),
+            lunar: (index == 0).then(|| Lunar {
+                id: format!("{}1", entry.id),
+                location: entry.location.moon(),
+                resource: entry.resource.clone(),
+                facility: entry.lunar_facility(),
+                fleet: entry.fleet(),
+            }),
+        })
+        .collect();
+
+    EmpireOverview {
+        overview,
+        technology,
+        maybe_fleet_events: None,
+    }
+}
+
+fn attack(arrival_seconds: i64) -> FleetEvent {
+    FleetEvent {
+        mission_type: MissionType::EnemyAttacking,
+        arrival_time: at(arrival_seconds).with_timezone(&FixedOffset::east_opt(8 * 3600).unwrap()),
+        coords_origin: coordinates("[2:100:8]"),
+        dest_coords: coordinates("[1:234:5]"),
+    }
+}
+
+static MIGRATOR: Migrator = sqlx::migrate!();
+
+async fn count(pool: &PgPool, sql: &str, account_id: i32) -> i64 {
+    sqlx::query(sql)
+        .bind(account_id)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+        .get(0)
+}
+
+#[sqlx::test]
+async fn ensure_account_is_idempotent(pool: PgPool) -> sqlx::Result<()> {
+    let id = ensure_account(&pool, "player@example.com", "s144-tw").await?;
+
+    assert_eq!(
+        ensure_account(&pool, "player@example.com", "s144-tw").await?,
+        id
+    );
+    assert_ne!(
+        ensure_account(&pool, "player@example.com", "s145-tw").await?,
+        id
+    );
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn save_empire_overview_upserts_per_account(pool: PgPool) -> sqlx::Result<()> {
+    let empire = empire();
+    let account_id = ensure_account(&pool, "player@example.com", "s144-tw").await?;
+    let other_id = ensure_account(&pool, "other@example.com", "s144-tw").await?;
+
+    save_empire_overview(&pool, account_id, &empire, at(0)).await?;
+    save_empire_overview(&pool, account_id, &empire, at(60)).await?;
+    // the same coordinates in another universe
+    save_empire_overview(&pool, other_id, &empire, at(0)).await?;
+
+    let planets = "SELECT COUNT(*) FROM planets WHERE account_id = $1";
+    assert_eq!(count(&pool, planets, account_id).await, 2);
+    assert_eq!(count(&pool, planets, other_id).await, 2);
+    let updated_at: DateTime<Utc> =
+        sqlx::query("SELECT updated_at FROM fleet WHERE account_id = $1 AND location = $2")
+            .bind(account_id)
+            .bind(coordinates("[1:234:5]"))
+            .fetch_one(&pool)
+            .await?
+            .get(0);
+    assert_eq!(updated_at, at(60));
+    // every save is a snapshot
+    let history = "SELECT COUNT(*) FROM fleet_history WHERE account_id = $1";
+    assert_eq!(count(&pool, history, account_id).await, 4);
+    assert_eq!(count(&pool, history, other_id).await, 2);
+    let lunar = "SELECT COUNT(*) FROM lunar_facility_history WHERE account_id = $1";
+    assert_eq!(count(&pool, lunar, account_id).await, 2);
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn lunar_level_changes_are_on_the_moon(pool: PgPool) -> sqlx::Result<()> {
+    let account_id = ensure_account(&pool, "player@example.com", "s144-tw").await?;
+    let mut empire = empire();
+    save_empire_overview(&pool, account_id, &empire, at(0)).await?;
+    let planet = &mut empire.overview[0];
+    let lunar = planet.lunar.as_mut().unwrap();
+    lunar.facility.sensor_phalanx += 1;
+    let (planet, moon) = (planet.location, lunar.location);
+    save_empire_overview(&pool, account_id, &empire, at(60)).await?;
+
+    let changes = level_changes_since(&pool, account_id, None, at(30)).await?;
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].location, moon);
+    assert_eq!(changes[0].category, "lunar_facility");
+    assert_eq!(changes[0].name, "sensor_phalanx");
+    assert_eq!(changes[0].level, changes[0].previous_level + 1);
+
+    let changes = level_changes_since(&pool, account_id, Some(&moon), at(30)).await?;
+    assert_eq!(changes.len(), 1);
+    assert!(
+        level_changes_since(&pool, account_id, Some(&planet), at(30))
+            .await?
+            .is_empty()
+    );
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn fleet_events_are_deduplicated_and_resolved(pool: PgPool) -> sqlx::Result<()> {
+    let account_id = ensure_account(&pool, "player@example.com", "s144-tw").await?;
+    let other_id = ensure_account(&pool, "other@example.com", "s144-tw").await?;
+    let (first, second) = (attack(3600), attack(7200));
+
+    // listed twice in one refresh is a single event
+    let changes =
+        record_fleet_events(&pool, account_id, &[first.clone(), first.clone()], at(0)).await?;
+    assert_eq!(changes.new.len(), 1);
+    assert!(changes.resolved.is_empty());
+
+    let changes =
+        record_fleet_events(&pool, account_id, &[first.clone(), second.clone()], at(60)).await?;
+    assert_eq!(changes.new.len(), 1);
+    assert_eq!(changes.new[0].event, second);
+    assert!(changes.resolved.is_empty());
+
+    let open = open_fleet_events(&pool, account_id).await?;
+    assert_eq!(open.len(), 2);
+    assert_eq!(open[0].event, first);
+    assert_eq!(open[0].first_seen_at, at(0));
+    assert_eq!(open[0].last_seen_at, at(60));
+
+    // the events of one account leave the other one alone
+    let changes = record_fleet_events(&pool, other_id, slice::from_ref(&second), at(90)).await?;
+    assert_eq!(changes.new.len(), 1);
+    assert!(changes.resolved.is_empty());
+    assert_eq!(open_fleet_events(&pool, account_id).await?.len(), 2);
+
+    // the first attack landed
+    let changes = record_fleet_events(&pool, account_id, slice::from_ref(&second), at(120)).await?;
+    assert!(changes.new.is_empty());
+    assert_eq!(changes.resolved.len(), 1);
+    assert_eq!(changes.resolved[0].event, first);
+    assert_eq!(changes.resolved[0].resolved_at, Some(at(120)));
+    let open = open_fleet_events(&pool, account_id).await?;
+    assert_eq!(open.len(), 1);
+    assert_eq!(open[0].event, second);
+    assert_eq!(open_fleet_events(&pool, other_id).await?.len(), 1);
+
+    // a resolved event is never reopened, listed again it is a new one
+    let changes = record_fleet_events(&pool, account_id, &[first.clone(), second], at(180)).await?;
+    assert_eq!(changes.new.len(), 1);
+    assert_ne!(changes.new[0].id, open[0].id);
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn saved_fleet_lifecycle(pool: PgPool) -> sqlx::Result<()> {
+    let account_id = ensure_account(&pool, "player@example.com", "s144-tw").await?;
+    let other_id = ensure_account(&pool, "other@example.com", "s144-tw").await?;
+    let fleet = SavedFleet {
+        planet_id: "33630196".to_owned(),
+        location: coordinates("[1:234:5]"),
+        destination: coordinates("[1:234:16]"),
+        mission: 15,
+        attack: attack(3600),
+        saved_at: at(0),
+        resolved_at: None,
+    };
+
+    let id = insert_saved_fleet(&pool, account_id, &fleet).await?;
+    let open = open_saved_fleets(&pool, account_id).await?;
+    assert_eq!(open.len(), 1);
+    assert_eq!(open[0].id, id);
+    assert_eq!(open[0].fleet, fleet);
+    assert!(open_saved_fleets(&pool, other_id).await?.is_empty());
+
+    resolve_saved_fleet(&pool, id, at(3600)).await?;
+    let open = open_saved_fleets(&pool, account_id).await?;
+    assert_eq!(open[0].fleet.resolved_at, Some(at(3600)));
+
+    close_saved_fleet(&pool, id, at(3660), true).await?;
+    assert!(open_saved_fleets(&pool, account_id).await?.is_empty());
+    let recalled: bool = sqlx::query("SELECT recalled FROM saved_fleets WHERE id = $1")
+        .bind(id)
+        .fetch_one(&pool)
+        .await?
+        .get(0);
+    assert!(recalled);
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = false)]
+async fn displayed_resources_are_migrated_to_numbers(pool: PgPool) -> sqlx::Result<()> {
+    // the schema as it was while resources were stored as displayed
+    let numeric_resource = 20230219120000;
+    Migrator {
+        migrations: Cow::Owned(
+            MIGRATOR
+                .migrations
+                .iter()
+                .filter(|migration| migration.version < numeric_resource)
+                .cloned()
+                .collect(),
+        ),
+        ignore_missing: false,
+        locking: true,
+    }
+    .run(&pool)
+    .await?;
+
+    sqlx::query("INSERT INTO planets (updated_at, location) VALUES ($1, '[1:234:5]')")
+        .bind(at(0))
+        .execute(&pool)
+        .await?;
+    sqlx::query(
+        r#"
+        INSERT INTO resource (updated_at, location, metal, crystal, deuterium, energy)
+        VALUES ($1, '[1:234:5]', '1.234,5Mrd', '1,234,567', '1.2Mn', '-120')
+        "#,
+    )
+    .bind(at(0))
+    .execute(&pool)
+    .await?;
+
+    MIGRATOR.run(&pool).await?;
+
+    let row = sqlx::query("SELECT metal, crystal, deuterium, energy FROM resource")
+        .fetch_one(&pool)
+        .await?;
+    assert_eq!(row.get::<i64, _>("metal"), 1_234_500_000_000);
+    assert_eq!(row.get::<i64, _>("crystal"), 1_234_567);
+    assert_eq!(row.get::<i64, _>("deuterium"), 1_200_000);
+    assert_eq!(row.get::<i64, _>("energy"), -120);
+
+    Ok(())
+}
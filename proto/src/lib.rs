@@ -0,0 +1,8 @@
+//! gRPC definition of the bot, `bot_client::BotClient` is the generated client
+//! and `bot_server::Bot` the service implemented by `ogc mono`.
+
+#![allow(clippy::derive_partial_eq_without_eq)]
+
+pub use prost_types;
+
+tonic::include_proto!("ogc.v1");